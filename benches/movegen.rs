@@ -0,0 +1,70 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use tetris::models::{
+    block::{Block, Color},
+    field::{Field, FieldConfig},
+    piece_set::PieceSet,
+    tetromino::{MoveDirection, RotateDirection},
+};
+
+// Every hard drop placement of every piece: turn it at spawn, push it against the left
+// wall, then drop it from each column on the way to the right wall. This is the search a
+// bot runs for each piece it is dealt.
+fn placements(set: &PieceSet, field: &Field) -> usize {
+    let mut count = 0;
+    for kind in set.kinds() {
+        for turns in 0..4 {
+            let mut piece = set.build(kind, field.config()).unwrap();
+            for _ in 0..turns {
+                piece.rotate_in(RotateDirection::Right, field);
+            }
+            while field.is_vacant(&piece.dry_move(MoveDirection::Left)) {
+                piece.move_(MoveDirection::Left);
+            }
+            loop {
+                black_box(piece.dry_drop(field));
+                count += 1;
+                if !field.is_vacant(&piece.dry_move(MoveDirection::Right)) {
+                    break;
+                }
+                piece.move_(MoveDirection::Right);
+            }
+        }
+    }
+    count
+}
+
+// A ragged stack eight rows high with one hole per row.
+fn stacked_field() -> Field {
+    let mut field = Field::new(FieldConfig::default());
+    for y in 0..8 {
+        let height = [8, 6, 7, 3, 5, 8, 2, 4, 6, 7];
+        let hole = (y * 3 + 1) % 10;
+        field.fix_blocks(
+            (0..10)
+                .filter(|x| *x != hole && y < height[*x as usize])
+                .map(|x| Block::new(Color::Cyan, x, y)),
+        );
+    }
+    field
+}
+
+fn bench_placements(c: &mut Criterion) {
+    let standard = PieceSet::standard();
+    let pentominoes = PieceSet::pentominoes();
+    let empty = Field::new(FieldConfig::default());
+    let stacked = stacked_field();
+
+    c.bench_function("placements/empty", |b| {
+        b.iter(|| placements(&standard, black_box(&empty)))
+    });
+    c.bench_function("placements/stacked", |b| {
+        b.iter(|| placements(&standard, black_box(&stacked)))
+    });
+    c.bench_function("placements/pentominoes", |b| {
+        b.iter(|| placements(&pentominoes, black_box(&stacked)))
+    });
+}
+
+criterion_group!(benches, bench_placements);
+criterion_main!(benches);
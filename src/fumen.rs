@@ -0,0 +1,656 @@
+//! Reads and writes fumen, the v115 board notation of fumen.zui.jp and harddrop.com.
+//!
+//! A fumen is a run of pages. Each page stores its field as the difference from the field
+//! the previous page leaves behind once its piece locks, then the piece and, when it changes,
+//! the comment, all packed into base64 digits.
+
+use std::rc::Rc;
+
+use anyhow::{anyhow, bail, ensure, Result};
+use derive_new::new;
+
+use crate::models::{
+    block::{Block, Color},
+    field::{Field, FieldConfig},
+    tetromino::{piece::Piece, shape::Shape, Tetromino, TetrominoDirection, TetrominoKind},
+};
+
+const TABLE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const COMMENT_TABLE: &[u8] =
+    b" !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~";
+const WIDTH: usize = 10;
+// Rows of the field proper; one more row of garbage waits under the floor.
+const TOP: usize = 23;
+const BLOCKS: usize = (TOP + 1) * WIDTH;
+// A field run that covers every cell without changing any, meaning the field repeats.
+const UNCHANGED: u32 = 8 * BLOCKS as u32 + BLOCKS as u32 - 1;
+const MAX_COMMENT: usize = 4095;
+
+/// One page of a fumen: the field before its piece locks, the piece and the comment shown.
+#[derive(Clone, Debug, Eq, new, PartialEq)]
+pub struct Page {
+    field: Field,
+    piece: Option<Piece>,
+    comment: Option<String>,
+}
+
+impl Page {
+    pub fn field(&self) -> &Field {
+        &self.field
+    }
+
+    pub fn piece(&self) -> &Option<Piece> {
+        &self.piece
+    }
+
+    pub fn comment(&self) -> &Option<String> {
+        &self.comment
+    }
+}
+
+/// Decodes a fumen, with or without the URL in front of it. Each page carries the comment
+/// in effect on it, and the garbage row under the floor is left out of its field.
+pub fn decode(fumen: &str) -> Result<Vec<Page>> {
+    let data = ["v115@", "m115@", "d115@"]
+        .iter()
+        .find_map(|prefix| fumen.find(prefix).map(|idx| &fumen[idx + prefix.len()..]))
+        .ok_or_else(|| anyhow!("not a v115 fumen"))?;
+    let data = data.split('&').next().unwrap_or_default();
+    let mut reader = Reader::new(data)?;
+
+    let mut pages = Vec::new();
+    let mut cells = [0; BLOCKS];
+    let mut repeat = 0;
+    let mut comment = String::new();
+    while !reader.is_empty() {
+        if repeat > 0 {
+            repeat -= 1;
+        } else if !read_field(&mut reader, &mut cells)? {
+            repeat = reader.poll(1)?;
+        }
+
+        let action = Action::from_value(reader.poll(3)?)?;
+        if action.comment {
+            let len = reader.poll(2)? as usize;
+            let mut escaped = String::new();
+            for _ in (0..len).step_by(4) {
+                let mut value = reader.poll(5)?;
+                for _ in 0..4 {
+                    let idx = (value % (COMMENT_TABLE.len() as u32 + 1)) as usize;
+                    escaped.push(*COMMENT_TABLE.get(idx).unwrap_or(&b' ') as char);
+                    value /= COMMENT_TABLE.len() as u32 + 1;
+                }
+            }
+            escaped.truncate(len);
+            comment = unescape(&escaped);
+        }
+
+        let piece = action.piece();
+        pages.push(Page::new(
+            to_field(&cells),
+            piece.clone(),
+            Some(comment.clone()).filter(|comment| !comment.is_empty()),
+        ));
+        if action.lock {
+            lock(&mut cells, piece.as_ref(), action.rise, action.mirror);
+        }
+    }
+    Ok(pages)
+}
+
+/// Encodes pages as a fumen. Fields must be 10 wide and only their bottom 23 rows are kept;
+/// pieces must be from the standard seven.
+pub fn encode(pages: &[Page]) -> Result<String> {
+    let mut writer = Writer::default();
+    let mut cells = [0; BLOCKS];
+    // Where the count of pages repeating the last field goes, while it can still grow.
+    let mut repeat: Option<usize> = None;
+    let mut comment = "";
+    for page in pages.iter() {
+        ensure!(
+            *page.field.config().width() == WIDTH,
+            "a fumen field is {} columns wide",
+            WIDTH
+        );
+        let current = from_field(&page.field);
+        let runs = field_runs(&cells, &current);
+        if runs != [UNCHANGED] {
+            for run in runs.iter() {
+                writer.push(*run, 2);
+            }
+            repeat = None;
+        } else {
+            match repeat {
+                Some(idx) if writer.0[idx] < TABLE.len() as u32 - 1 => writer.0[idx] += 1,
+                _ => {
+                    writer.push(UNCHANGED, 2);
+                    writer.push(0, 1);
+                    repeat = Some(writer.0.len() - 1);
+                }
+            }
+        }
+
+        let text = page.comment.as_deref().unwrap_or_default();
+        let action = Action::new(page.piece.as_ref(), text != comment)?;
+        writer.push(action.to_value(), 3);
+        if action.comment {
+            let escaped = escape(text);
+            ensure!(
+                escaped.len() <= MAX_COMMENT,
+                "comments are at most {} characters once escaped",
+                MAX_COMMENT
+            );
+            writer.push(escaped.len() as u32, 2);
+            for chunk in escaped.as_bytes().chunks(4) {
+                let value = chunk.iter().rev().fold(0, |value, ch| {
+                    let idx = COMMENT_TABLE.iter().position(|c| c == ch).unwrap_or(0);
+                    value * (COMMENT_TABLE.len() as u32 + 1) + idx as u32
+                });
+                writer.push(value, 5);
+            }
+            comment = text;
+        }
+
+        cells = current;
+        lock(&mut cells, page.piece.as_ref(), false, false);
+    }
+
+    // A `?` follows the first 42 characters and every 47 after that.
+    let data: String = writer
+        .0
+        .iter()
+        .map(|d| TABLE[*d as usize] as char)
+        .collect();
+    let mut fumen = String::from("v115@");
+    for (idx, ch) in data.chars().enumerate() {
+        if idx >= 42 && (idx - 42) % 47 == 0 {
+            fumen.push('?');
+        }
+        fumen.push(ch);
+    }
+    Ok(fumen)
+}
+
+// Base64 digits, least significant first.
+struct Reader {
+    digits: Vec<u32>,
+    pos: usize,
+}
+
+impl Reader {
+    fn new(data: &str) -> Result<Self> {
+        let digits = data
+            .chars()
+            .filter(|ch| *ch != '?' && !ch.is_whitespace())
+            .map(|ch| {
+                TABLE
+                    .iter()
+                    .position(|d| *d as char == ch)
+                    .map(|d| d as u32)
+                    .ok_or_else(|| anyhow!("unexpected {:?} in fumen", ch))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { digits, pos: 0 })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.digits.len()
+    }
+
+    fn poll(&mut self, len: usize) -> Result<u32> {
+        let digits = self
+            .digits
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| anyhow!("fumen ends early"))?;
+        self.pos += len;
+        Ok(digits.iter().rev().fold(0, |value, d| value * 64 + d))
+    }
+}
+
+#[derive(Default)]
+struct Writer(Vec<u32>);
+
+impl Writer {
+    fn push(&mut self, mut value: u32, len: usize) {
+        for _ in 0..len {
+            self.0.push(value % 64);
+            value /= 64;
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Action {
+    kind: u32,
+    rotation: u32,
+    coordinate: u32,
+    rise: bool,
+    mirror: bool,
+    comment: bool,
+    lock: bool,
+}
+
+impl Action {
+    fn new(piece: Option<&Piece>, comment: bool) -> Result<Self> {
+        let (kind, rotation, coordinate) = match piece {
+            Some(piece) => {
+                let kind = kind_number(piece.kind())?;
+                let rotation = rotation_number(*piece.dir());
+                let (x, y) = centre(piece);
+                let (x, y) = shift(kind, rotation, x, y, -1);
+                let coordinate = (TOP as i32 - 1 - y) * WIDTH as i32 + x;
+                ensure!(
+                    (0..BLOCKS as i32).contains(&coordinate),
+                    "the piece is off the fumen field"
+                );
+                (kind, rotation, coordinate as u32)
+            }
+            None => (0, 0, 0),
+        };
+        Ok(Self {
+            kind,
+            rotation,
+            coordinate,
+            rise: false,
+            mirror: false,
+            comment,
+            lock: true,
+        })
+    }
+
+    fn from_value(mut value: u32) -> Result<Self> {
+        let mut next = |radix: u32| {
+            let digit = value % radix;
+            value /= radix;
+            digit
+        };
+        let (kind, rotation, coordinate) = (next(8), next(4), next(BLOCKS as u32));
+        let (rise, mirror) = (next(2) != 0, next(2) != 0);
+        // Whether the piece is drawn in its own color or in gray doesn't matter here.
+        let _colorize = next(2);
+        let (comment, lock) = (next(2) != 0, next(2) == 0);
+        let action = Self {
+            kind,
+            rotation,
+            coordinate,
+            rise,
+            mirror,
+            comment,
+            lock,
+        };
+        ensure!(value == 0, "unexpected action in fumen");
+        Ok(action)
+    }
+
+    fn to_value(self) -> u32 {
+        // Fumen stores whether the piece doesn't lock, and pieces are always drawn in color.
+        let flags = [!self.lock, self.comment, true, self.mirror, self.rise];
+        let flags = flags
+            .iter()
+            .fold(0, |value, flag| value * 2 + u32::from(*flag));
+        ((flags * BLOCKS as u32 + self.coordinate) * 4 + self.rotation) * 8 + self.kind
+    }
+
+    fn piece(&self) -> Option<Piece> {
+        let shape = match self.kind {
+            1 => Shape::i(),
+            2 => Shape::l(),
+            3 => Shape::o(),
+            4 => Shape::z(),
+            5 => Shape::t(),
+            6 => Shape::j(),
+            7 => Shape::s(),
+            _ => return None,
+        };
+        let dir = match self.rotation {
+            0 => TetrominoDirection::South,
+            1 => TetrominoDirection::East,
+            2 => TetrominoDirection::North,
+            _ => TetrominoDirection::West,
+        };
+        let x = (self.coordinate as usize % WIDTH) as i32;
+        let y = TOP as i32 - 1 - (self.coordinate as usize / WIDTH) as i32;
+        let (x, y) = shift(self.kind, self.rotation, x, y, 1);
+        // The O turns about the middle of its square in fumen but sits on its bottom left
+        // cell here.
+        let (x, y) = match (self.kind, dir) {
+            (3, TetrominoDirection::East) => (x, y - 1),
+            (3, TetrominoDirection::South) => (x - 1, y - 1),
+            (3, TetrominoDirection::West) => (x - 1, y),
+            _ => (x, y),
+        };
+        let axis = Block::new(*shape.color(), x, y);
+        Some(Piece::new(Rc::new(shape), dir, axis))
+    }
+}
+
+// The rotation centre of a piece as fumen places it.
+fn centre(piece: &Piece) -> (i32, i32) {
+    let (x, y) = (*piece.axis().x(), *piece.axis().y());
+    match (piece.kind(), piece.dir()) {
+        (TetrominoKind::O, TetrominoDirection::East) => (x, y + 1),
+        (TetrominoKind::O, TetrominoDirection::South) => (x + 1, y + 1),
+        (TetrominoKind::O, TetrominoDirection::West) => (x + 1, y),
+        _ => (x, y),
+    }
+}
+
+// Fumen stores some pieces a cell off their rotation centre; `sign` 1 moves from the stored
+// cell to the centre and -1 back.
+fn shift(kind: u32, rotation: u32, x: i32, y: i32, sign: i32) -> (i32, i32) {
+    let (dx, dy) = match (kind, rotation) {
+        (3, 3) => (1, -1),
+        (3, 0) => (1, 0),
+        (3, 2) => (0, -1),
+        (1, 0) => (1, 0),
+        (1, 3) => (0, -1),
+        (7, 2) | (4, 2) => (0, -1),
+        (7, 1) => (-1, 0),
+        (4, 3) => (1, 0),
+        _ => (0, 0),
+    };
+    (x + sign * dx, y + sign * dy)
+}
+
+fn kind_number(kind: TetrominoKind) -> Result<u32> {
+    match kind {
+        TetrominoKind::I => Ok(1),
+        TetrominoKind::L => Ok(2),
+        TetrominoKind::O => Ok(3),
+        TetrominoKind::Z => Ok(4),
+        TetrominoKind::T => Ok(5),
+        TetrominoKind::J => Ok(6),
+        TetrominoKind::S => Ok(7),
+        TetrominoKind::Custom(_) => bail!("fumen has only the standard seven pieces"),
+    }
+}
+
+fn rotation_number(dir: TetrominoDirection) -> u32 {
+    match dir {
+        TetrominoDirection::South => 0,
+        TetrominoDirection::East => 1,
+        TetrominoDirection::North => 2,
+        TetrominoDirection::West => 3,
+    }
+}
+
+fn color_number(color: Color) -> u8 {
+    match color {
+        Color::Cyan => 1,
+        Color::Orange => 2,
+        Color::Yellow => 3,
+        Color::Red => 4,
+        Color::Purple => 5,
+        Color::Blue => 6,
+        Color::Green => 7,
+        Color::Gray => 8,
+    }
+}
+
+fn number_color(number: u8) -> Option<Color> {
+    match number {
+        1 => Some(Color::Cyan),
+        2 => Some(Color::Orange),
+        3 => Some(Color::Yellow),
+        4 => Some(Color::Red),
+        5 => Some(Color::Purple),
+        6 => Some(Color::Blue),
+        7 => Some(Color::Green),
+        8 => Some(Color::Gray),
+        _ => None,
+    }
+}
+
+// Cells run top row first, with the garbage row, at `y` -1, last.
+fn index(x: i32, y: i32) -> Option<usize> {
+    let row = TOP as i32 - 1 - y;
+    if (0..WIDTH as i32).contains(&x) && (0..=TOP as i32).contains(&row) {
+        Some(row as usize * WIDTH + x as usize)
+    } else {
+        None
+    }
+}
+
+fn to_field(cells: &[u8; BLOCKS]) -> Field {
+    let mut field = Field::new(FieldConfig::default());
+    for y in 0..TOP as i32 {
+        field.fix_blocks((0..WIDTH as i32).filter_map(|x| {
+            let color = number_color(cells[index(x, y)?])?;
+            Some(Block::new(color, x, y))
+        }));
+    }
+    field
+}
+
+fn from_field(field: &Field) -> [u8; BLOCKS] {
+    let mut cells = [0; BLOCKS];
+    for y in 0..TOP as i32 {
+        for x in 0..WIDTH as i32 {
+            if let (Some(idx), Some(color)) = (index(x, y), field.color(x, y)) {
+                cells[idx] = color_number(color);
+            }
+        }
+    }
+    cells
+}
+
+// Returns whether the field changed.
+fn read_field(reader: &mut Reader, cells: &mut [u8; BLOCKS]) -> Result<bool> {
+    let mut idx = 0;
+    let mut changed = true;
+    while idx < BLOCKS {
+        let run = reader.poll(2)?;
+        if run == UNCHANGED {
+            changed = false;
+        }
+        let (diff, len) = (
+            (run / BLOCKS as u32) as i32,
+            (run % BLOCKS as u32) as usize + 1,
+        );
+        ensure!(idx + len <= BLOCKS, "field runs past the end in fumen");
+        for cell in cells[idx..idx + len].iter_mut() {
+            let number = *cell as i32 + diff - 8;
+            ensure!((0..=8).contains(&number), "unexpected block in fumen");
+            *cell = number as u8;
+        }
+        idx += len;
+    }
+    Ok(changed)
+}
+
+// Splits the difference from `prev` to `current` into runs of equal cells.
+fn field_runs(prev: &[u8; BLOCKS], current: &[u8; BLOCKS]) -> Vec<u32> {
+    let diffs: Vec<u32> = prev
+        .iter()
+        .zip(current.iter())
+        .map(|(prev, current)| (*current as i32 - *prev as i32 + 8) as u32)
+        .collect();
+    let mut runs = Vec::new();
+    let mut start = 0;
+    for idx in 1..=BLOCKS {
+        if idx == BLOCKS || diffs[idx] != diffs[start] {
+            runs.push(diffs[start] * BLOCKS as u32 + (idx - start - 1) as u32);
+            start = idx;
+        }
+    }
+    runs
+}
+
+// Locks the piece, clears full rows, and then raises the garbage row into the field or
+// mirrors the field if the page asks to.
+fn lock(cells: &mut [u8; BLOCKS], piece: Option<&Piece>, rise: bool, mirror: bool) {
+    if let Some(piece) = piece {
+        for block in piece.blocks().iter() {
+            if let Some(idx) = index(*block.x(), *block.y()) {
+                cells[idx] = color_number(*block.color());
+            }
+        }
+    }
+    let field = TOP * WIDTH;
+    let mut rows: Vec<[u8; WIDTH]> = cells[..field]
+        .chunks(WIDTH)
+        .filter(|row| row.contains(&0))
+        .map(|row| row.try_into().unwrap())
+        .collect();
+    if rise {
+        rows.push(cells[field..].try_into().unwrap());
+        cells[field..].fill(0);
+    }
+    if mirror {
+        for row in rows.iter_mut() {
+            row.reverse();
+        }
+    }
+    while rows.len() < TOP {
+        rows.insert(0, [0; WIDTH]);
+    }
+    for (row, cells) in rows
+        .iter()
+        .skip(rows.len() - TOP)
+        .zip(cells.chunks_mut(WIDTH))
+    {
+        cells.copy_from_slice(row);
+    }
+}
+
+// JavaScript's `escape`, which fumen applies to comments.
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for unit in text.encode_utf16() {
+        match char::from_u32(unit as u32) {
+            Some(ch) if ch.is_ascii_alphanumeric() || "@*_+-./".contains(ch) => escaped.push(ch),
+            _ if unit < 256 => escaped.push_str(&format!("%{:02X}", unit)),
+            _ => escaped.push_str(&format!("%u{:04X}", unit)),
+        }
+    }
+    escaped
+}
+
+fn unescape(escaped: &str) -> String {
+    let mut units = Vec::new();
+    let mut rest = escaped;
+    while let Some(ch) = rest.chars().next() {
+        let code = [("%u", 4), ("%", 2)].iter().find_map(|(prefix, digits)| {
+            let hex = rest.strip_prefix(prefix)?.get(..*digits)?;
+            if !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+                return None;
+            }
+            Some((u16::from_str_radix(hex, 16).ok()?, prefix.len() + digits))
+        });
+        match code {
+            Some((unit, len)) => {
+                units.push(unit);
+                rest = &rest[len..];
+            }
+            None => {
+                let mut buf = [0; 2];
+                units.extend_from_slice(ch.encode_utf16(&mut buf));
+                rest = &rest[ch.len_utf8()..];
+            }
+        }
+    }
+    String::from_utf16_lossy(&units)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::tetromino::MoveDirection;
+
+    #[test]
+    fn test_decode() {
+        let fumen = "v115@9gF8DeF8DeF8DeF8NeAgH";
+        let pages = decode(fumen).unwrap();
+        assert_eq!(1, pages.len());
+        let expected = "GGGGGG____\nGGGGGG____\nGGGGGG____\nGGGGGG____";
+        assert_eq!(expected, pages[0].field().to_string());
+        assert_eq!(&None, pages[0].piece());
+        assert_eq!(&None, pages[0].comment());
+        assert_eq!(fumen, encode(&pages).unwrap());
+
+        let pages = decode("http://fumen.zui.jp/?v115@vhARQJ").unwrap();
+        let piece = pages[0].piece().as_ref().unwrap();
+        assert_eq!(TetrominoKind::I, piece.kind());
+        let cells: Vec<(i32, i32)> = piece
+            .blocks()
+            .iter()
+            .map(|block| (*block.x(), *block.y()))
+            .collect();
+        assert_eq!(vec![(3, 0), (4, 0), (5, 0), (6, 0)], cells);
+    }
+
+    #[test]
+    fn test_decode_error() {
+        assert!(decode("v110@vhAAgH").is_err());
+        assert!(decode("v115@vhAA").is_err());
+        assert!(decode("v115@vh!AgH").is_err());
+    }
+
+    #[test]
+    fn test_encode() {
+        let empty = Field::new(FieldConfig::default());
+        assert_eq!(
+            "v115@vhAAgH",
+            encode(&[Page::new(empty, None, None)]).unwrap()
+        );
+
+        let wide = Field::new(FieldConfig::new(12, 24, 20));
+        assert!(encode(&[Page::new(wide, None, None)]).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut pages = Vec::new();
+        let mut field: Field = "ZZ__GGGGGG\nZZZ_GGGGGG".parse().unwrap();
+        let shapes = [
+            Shape::i,
+            Shape::j,
+            Shape::l,
+            Shape::o,
+            Shape::s,
+            Shape::t,
+            Shape::z,
+        ];
+        let dirs = [
+            TetrominoDirection::North,
+            TetrominoDirection::East,
+            TetrominoDirection::South,
+            TetrominoDirection::West,
+        ];
+        for (idx, shape) in shapes.iter().enumerate() {
+            for dir in dirs.iter() {
+                let shape = shape();
+                let axis = Block::new(*shape.color(), 4, 18);
+                let mut piece = Piece::new(Rc::new(shape), *dir, axis);
+                piece.drop_in(&field);
+                let comment = match idx {
+                    0 => None,
+                    1 => Some(String::from("T-spin double? 100% ✓")),
+                    _ => Some(format!("page {}", pages.len())),
+                };
+                pages.push(Page::new(field.clone(), Some(piece.clone()), comment));
+                field.fix_blocks(piece.blocks());
+                field.clear_blocks();
+            }
+        }
+        // Pages repeating a field with no piece, more than one counter can hold.
+        for _ in 0..70 {
+            pages.push(Page::new(field.clone(), None, None));
+        }
+        let mut piece = pages[5].piece().clone().unwrap();
+        piece.move_(MoveDirection::Up);
+        pages.push(Page::new(field.clone(), Some(piece), None));
+
+        let fumen = encode(&pages).unwrap();
+        assert!(fumen.contains('?'));
+        assert_eq!(pages, decode(&fumen).unwrap());
+    }
+
+    #[test]
+    fn test_escape() {
+        let text = "T-spin double? 100% ✓";
+        assert_eq!("T-spin%20double%3F%20100%25%20%u2713", escape(text));
+        assert_eq!(text, unescape(&escape(text)));
+        assert_eq!("100%", unescape("100%"));
+    }
+}
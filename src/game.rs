@@ -0,0 +1,773 @@
+use crate::input::{
+    auto_shift::AutoShift,
+    button::{Button, Buttons},
+};
+use crate::models::{
+    block::{Block, Blocks},
+    field::Field,
+    lock_delay::LockDelay,
+    score::{Score, ScoreTable},
+    tetromino::{MoveDirection, RotateDirection, Spin, Tetromino, TetrominoKind},
+    tetromino_factory::TetrominoFactory,
+};
+use crate::settings::Settings;
+
+pub const FRAME: f64 = 1000.0 / 60.0;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Input {
+    Press(Button),
+    Release(Button),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Event {
+    Moved(MoveDirection),
+    Rotated(RotateDirection),
+    SoftDropped(i32),
+    SonicDropped(i32),
+    HardDropped(i32),
+    Held(TetrominoKind),
+    Spun(TetrominoKind, Spin),
+    Locked(i32),
+    Combo(u32),
+    BackToBack,
+    PerfectClear(i32),
+    Spawned(TetrominoKind),
+    LevelUp(u32),
+    ToppedOut(TopOut),
+    Restarted,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum State {
+    Playing,
+    ToppedOut(TopOut),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TopOut {
+    BlockOut,
+    LockOut,
+    PartialLockOut,
+}
+
+pub struct Game {
+    field: Field,
+    tetromino_factory: TetrominoFactory,
+    tetromino: Box<dyn Tetromino>,
+    preview: Vec<TetrominoKind>,
+    hold: Option<TetrominoKind>,
+    can_hold: bool,
+    lock_delay: LockDelay,
+    buttons: Buttons,
+    auto_shift: AutoShift,
+    score: Score,
+    score_table: Box<dyn ScoreTable>,
+    settings: Settings,
+    state: State,
+    pieces: u32,
+    frame: u64,
+    clock: f64,
+    fall_rows: f64,
+    events: Vec<Event>,
+}
+
+impl Game {
+    pub fn new(settings: Settings) -> Self {
+        let field = Field::new(*settings.field());
+        let mut tetromino_factory = match settings.seed() {
+            Some(seed) => TetrominoFactory::with_seed(*seed),
+            None => TetrominoFactory::new(),
+        }
+        .with_piece_set(settings.piece_set().clone())
+        .with_field_config(*settings.field())
+        .with_randomizer(settings.randomizer().build(&settings.piece_set().kinds()));
+        let tetromino = tetromino_factory.pop();
+        let preview = tetromino_factory.peek(*settings.previews());
+        let mut lock_delay = LockDelay::new(*settings.lock_delay(), *settings.max_lock_resets());
+        lock_delay.spawn(Self::bottom(&tetromino.blocks()));
+        let auto_shift = AutoShift::new(settings.das().millis(), settings.arr().millis());
+        Self {
+            field,
+            tetromino_factory,
+            tetromino,
+            preview,
+            hold: None,
+            can_hold: true,
+            lock_delay,
+            buttons: Buttons::default(),
+            auto_shift,
+            score: Score::new(*settings.level()),
+            score_table: settings.scoring().table(),
+            settings,
+            state: State::Playing,
+            pieces: 0,
+            frame: 0,
+            clock: 0.0,
+            fall_rows: 0.0,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn field(&self) -> &Field {
+        &self.field
+    }
+
+    pub fn tetromino(&self) -> &dyn Tetromino {
+        self.tetromino.as_ref()
+    }
+
+    /// Returns the kinds of the upcoming pieces, next first.
+    pub fn preview(&self) -> &Vec<TetrominoKind> {
+        &self.preview
+    }
+
+    pub fn hold(&self) -> &Option<TetrominoKind> {
+        &self.hold
+    }
+
+    pub fn score(&self) -> &Score {
+        &self.score
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// Returns the seed the pieces are dealt from, which replays the same game when passed to
+    /// `Settings::with_seed`.
+    pub fn seed(&self) -> &u64 {
+        self.tetromino_factory.seed()
+    }
+
+    /// Returns how many pieces have locked.
+    pub fn pieces(&self) -> &u32 {
+        &self.pieces
+    }
+
+    pub fn frame(&self) -> &u64 {
+        &self.frame
+    }
+
+    /// Returns where the current piece would land.
+    pub fn ghost(&self) -> Blocks {
+        self.tetromino.dry_drop(&self.field)
+    }
+
+    pub fn apply(&mut self, input: Input) -> Vec<Event> {
+        if input == Input::Press(Button::Restart) {
+            *self = Self::new(self.settings.clone());
+            return vec![Event::Restarted];
+        }
+        if self.state != State::Playing {
+            return Vec::new();
+        }
+
+        let now = self.now();
+        match input {
+            Input::Press(button) => {
+                self.buttons.press(button);
+                self.press(button, now);
+            }
+            Input::Release(button) => {
+                self.buttons.release(button);
+                self.release(button, now);
+            }
+        }
+        self.events.drain(..).collect()
+    }
+
+    /// Advances the clock by `dt` milliseconds, one fixed frame at a time.
+    pub fn tick(&mut self, dt: f64) -> Vec<Event> {
+        self.clock += dt;
+        // The tolerance keeps float drift in `dt` from dropping a frame.
+        while self.state == State::Playing && (self.frame + 1) as f64 * FRAME <= self.clock + 1e-6 {
+            self.step();
+        }
+        self.events.drain(..).collect()
+    }
+}
+
+impl Game {
+    fn now(&self) -> f64 {
+        self.frame as f64 * FRAME
+    }
+
+    fn step(&mut self) {
+        self.frame += 1;
+        let now = self.now();
+
+        if let Some(move_dir) = *self.auto_shift.dir() {
+            let steps = self.auto_shift.tick(now);
+            self.shift(move_dir, steps, now);
+        }
+        self.fall(now);
+
+        if self.lock_delay.is_expired(now) {
+            self.lock();
+        }
+    }
+
+    fn press(&mut self, button: Button, now: f64) {
+        match button {
+            Button::Left | Button::Right => {
+                let move_dir = if button == Button::Left {
+                    MoveDirection::Left
+                } else {
+                    MoveDirection::Right
+                };
+                self.auto_shift.press(move_dir, now);
+                self.shift(move_dir, 1, now);
+            }
+            Button::RotateLeft | Button::RotateRight => {
+                let rotate_dir = if button == Button::RotateLeft {
+                    RotateDirection::Left
+                } else {
+                    RotateDirection::Right
+                };
+                if self.tetromino.rotate_in(rotate_dir, &self.field) {
+                    self.refresh_lock_delay(now);
+                    self.events.push(Event::Rotated(rotate_dir));
+                }
+            }
+            Button::HardDrop => {
+                let rows = self.tetromino.drop_in(&self.field);
+                self.score.hard_drop(self.score_table.as_ref(), rows);
+                self.events.push(Event::HardDropped(rows));
+                self.lock();
+            }
+            Button::SonicDrop => {
+                let rows = self.tetromino.drop_in(&self.field);
+                if rows > 0 {
+                    self.lock_delay.fall(Self::bottom(&self.tetromino.blocks()));
+                    self.lock_delay.land(now);
+                } else {
+                    self.refresh_lock_delay(now);
+                }
+                self.events.push(Event::SonicDropped(rows));
+            }
+            Button::Hold => self.swap_hold(),
+            Button::ToggleGhost => self.settings.toggle_ghost(),
+            Button::Restart => {}
+            Button::SoftDrop => self.fall_rows = self.fall_rows.max(1.0),
+        }
+    }
+
+    fn release(&mut self, button: Button, now: f64) {
+        let move_dir = match button {
+            Button::Left => MoveDirection::Left,
+            Button::Right => MoveDirection::Right,
+            _ => return,
+        };
+        self.auto_shift.release(move_dir);
+        if self.auto_shift.dir().is_none() {
+            // Fall back to the other direction if it is still held.
+            if self.buttons.is_held(Button::Left) {
+                self.auto_shift.press(MoveDirection::Left, now);
+            } else if self.buttons.is_held(Button::Right) {
+                self.auto_shift.press(MoveDirection::Right, now);
+            }
+        }
+    }
+
+    fn swap_hold(&mut self) {
+        if !self.can_hold {
+            return;
+        }
+        let held = self.tetromino.kind();
+        let tetromino = match self.hold {
+            Some(kind) => self
+                .tetromino_factory
+                .build(kind)
+                .expect("the held piece comes from the piece set"),
+            None => self.next(),
+        };
+        self.hold = Some(held);
+        self.can_hold = false;
+        self.events.push(Event::Held(held));
+        self.spawn(tetromino);
+    }
+
+    fn lock(&mut self) {
+        let blocks = self.tetromino.blocks();
+        let spin = self.tetromino.spin(&self.field);
+        let top_out = if !self.field.can_fix(&blocks) {
+            Some(TopOut::LockOut)
+        } else if *self.settings.partial_lock_out() && !self.field.can_fix_entirely(&blocks) {
+            Some(TopOut::PartialLockOut)
+        } else {
+            None
+        };
+
+        self.field.fix_blocks(blocks);
+        self.pieces += 1;
+        if let Some(top_out) = top_out {
+            self.top_out(top_out);
+            return;
+        }
+        let lines = self.field.clear_blocks();
+        let level = *self.score.level();
+        let back_to_back = *self.score.back_to_back();
+        self.score.clear(self.score_table.as_ref(), lines, spin);
+        if spin != Spin::None {
+            self.events.push(Event::Spun(self.tetromino.kind(), spin));
+        }
+        self.events.push(Event::Locked(lines));
+        if let Some(combo) = self.score.combo().filter(|combo| *combo > 0) {
+            self.events.push(Event::Combo(combo));
+        }
+        if lines > 0 && back_to_back && *self.score.back_to_back() {
+            self.events.push(Event::BackToBack);
+        }
+        if lines > 0 && self.field.is_empty() {
+            let table = self.score_table.as_ref();
+            self.score.perfect_clear(table, lines, back_to_back, level);
+            self.events.push(Event::PerfectClear(lines));
+        }
+        if *self.score.level() > level {
+            self.events.push(Event::LevelUp(*self.score.level()));
+        }
+
+        let tetromino = self.next();
+        self.can_hold = true;
+        self.spawn(tetromino);
+    }
+
+    fn next(&mut self) -> Box<dyn Tetromino> {
+        let tetromino = self.tetromino_factory.pop();
+        self.preview = self.tetromino_factory.peek(*self.settings.previews());
+        tetromino
+    }
+
+    fn spawn(&mut self, tetromino: Box<dyn Tetromino>) {
+        self.tetromino = tetromino;
+        self.lock_delay
+            .spawn(Self::bottom(&self.tetromino.blocks()));
+        self.events.push(Event::Spawned(self.tetromino.kind()));
+        if !self.field.is_vacant(&self.tetromino.blocks()) {
+            self.top_out(TopOut::BlockOut);
+            return;
+        }
+        if self.settings.gravity().is_instant(*self.score.level()) {
+            self.tetromino.drop_in(&self.field);
+            self.lock_delay.fall(Self::bottom(&self.tetromino.blocks()));
+        }
+    }
+
+    fn top_out(&mut self, top_out: TopOut) {
+        self.state = State::ToppedOut(top_out);
+        self.events.push(Event::ToppedOut(top_out));
+    }
+
+    fn shift(&mut self, move_dir: MoveDirection, steps: u32, now: f64) {
+        for _ in 0..steps {
+            if !self.field.is_vacant(&self.tetromino.dry_move(move_dir)) {
+                break;
+            }
+            self.tetromino.move_(move_dir);
+            self.refresh_lock_delay(now);
+            self.events.push(Event::Moved(move_dir));
+        }
+    }
+
+    fn fall(&mut self, now: f64) {
+        let level = *self.score.level();
+        let soft_drop = self.buttons.is_held(Button::SoftDrop);
+        let mut gravity = self.settings.gravity().rows_per_frame(level);
+        if soft_drop {
+            gravity *= *self.settings.sdf();
+        }
+        self.fall_rows += gravity;
+        while self.fall_rows >= 1.0 {
+            let blocks = self.tetromino.dry_move(MoveDirection::Down);
+            if !self.field.is_vacant(&blocks) {
+                self.lock_delay.land(now);
+                self.fall_rows = 0.0;
+                return;
+            }
+            self.tetromino.move_(MoveDirection::Down);
+            self.lock_delay.fall(Self::bottom(&blocks));
+            self.fall_rows -= 1.0;
+            if soft_drop {
+                self.score.soft_drop(self.score_table.as_ref(), 1);
+                self.events.push(Event::SoftDropped(1));
+            }
+        }
+    }
+
+    fn refresh_lock_delay(&mut self, now: f64) {
+        let blocks = self.tetromino.blocks();
+        let grounded = !self
+            .field
+            .is_vacant(&self.tetromino.dry_move(MoveDirection::Down));
+        self.lock_delay
+            .refresh(now, Self::bottom(&blocks), grounded);
+    }
+
+    fn bottom(blocks: &[Block]) -> i32 {
+        blocks.iter().map(|block| *block.y()).min().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::models::{
+        block::{Color, Direction},
+        field::FieldConfig,
+        gravity::Gravity,
+        piece_set::PieceSet,
+        tetromino::{piece::Piece, shape::Shape, TetrominoDirection},
+    };
+
+    #[test]
+    fn test_hard_drop() {
+        let mut game = Game::new(Settings::default());
+        let kind = game.tetromino().kind();
+        let events = game.apply(Input::Press(Button::HardDrop));
+        assert!(matches!(events[0], Event::HardDropped(rows) if rows > 0));
+        assert_eq!(Event::Locked(0), events[1]);
+        assert_eq!(Event::Spawned(game.tetromino().kind()), events[2]);
+        assert_eq!(4, game.field().blocks().len());
+        assert_ne!(kind, game.tetromino().kind());
+        assert!(*game.score().points() > 0);
+    }
+
+    #[test]
+    fn test_preview() {
+        let mut game = Game::new(Settings::default().with_previews(3));
+        let preview = game.preview().clone();
+        assert_eq!(3, preview.len());
+        game.apply(Input::Press(Button::HardDrop));
+        assert_eq!(preview[0], game.tetromino().kind());
+        assert_eq!(preview[1..], game.preview()[..2]);
+    }
+
+    #[test]
+    fn test_seed() {
+        let game = Game::new(Settings::default());
+        let replayed = Game::new(Settings::default().with_seed(*game.seed()));
+        assert_eq!(game.tetromino().kind(), replayed.tetromino().kind());
+        assert_eq!(game.preview(), replayed.preview());
+    }
+
+    #[test]
+    fn test_piece_set() {
+        let settings = Settings::default().with_piece_set(PieceSet::pentominoes());
+        let mut game = Game::new(settings);
+        assert_eq!(5, game.tetromino().blocks().len());
+        game.apply(Input::Press(Button::HardDrop));
+        assert_eq!(5, game.field().blocks().len());
+        assert!(matches!(game.tetromino().kind(), TetrominoKind::Custom(_)));
+    }
+
+    #[test]
+    fn test_field_config() {
+        for width in [4, 20] {
+            let settings = Settings::default()
+                .with_field(FieldConfig::new(width, 24, 20))
+                .unwrap();
+            let mut game = Game::new(settings);
+            let blocks = game.tetromino().blocks();
+            assert!(blocks.iter().all(|block| *block.x() < width as i32));
+            game.apply(Input::Press(Button::HardDrop));
+            // An I fills a whole row of the narrow field.
+            let cleared = width * *game.score().lines() as usize;
+            assert_eq!(4, game.field().blocks().len() + cleared);
+        }
+    }
+
+    #[test]
+    fn test_hold() {
+        let mut game = Game::new(Settings::default());
+        let first = game.tetromino().kind();
+        let events = game.apply(Input::Press(Button::Hold));
+        let second = game.tetromino().kind();
+        assert_eq!(vec![Event::Held(first), Event::Spawned(second)], events);
+        game.apply(Input::Release(Button::Hold));
+        assert_eq!(Vec::<Event>::new(), game.apply(Input::Press(Button::Hold)));
+        assert_eq!(second, game.tetromino().kind());
+        assert_eq!(Some(first), *game.hold());
+    }
+
+    #[test]
+    fn test_auto_shift() {
+        let mut game = Game::new(Settings::default());
+        let events = game.apply(Input::Press(Button::Left));
+        assert_eq!(vec![Event::Moved(MoveDirection::Left)], events);
+        let events = game.tick(FRAME * 9.0);
+        assert!(!events.contains(&Event::Moved(MoveDirection::Left)));
+        let events = game.tick(FRAME * 30.0);
+        assert!(events.contains(&Event::Moved(MoveDirection::Left)));
+        assert!(game
+            .tetromino()
+            .blocks()
+            .iter()
+            .any(|block| *block.x() == 0));
+    }
+
+    #[test]
+    fn test_lock_delay() {
+        let mut game = Game::new(Settings::default());
+        game.apply(Input::Press(Button::SonicDrop));
+        assert!(!game.tick(FRAME * 29.0).contains(&Event::Locked(0)));
+        assert!(game.tick(FRAME).contains(&Event::Locked(0)));
+    }
+
+    #[test]
+    fn test_max_lock_resets_1() {
+        // Turning back and forth lifts the T off the floor every other time, which must not
+        // give it a fresh delay once the resets are used up.
+        let mut game = Game::new(Settings::default());
+        game.tetromino = Box::new(Piece::new(
+            Rc::new(Shape::t()),
+            TetrominoDirection::North,
+            Block::new(Color::Purple, 4, 0),
+        ));
+        for _ in 0..200 {
+            for button in [Button::RotateRight, Button::RotateLeft] {
+                game.apply(Input::Press(button));
+                game.apply(Input::Release(button));
+                game.tick(FRAME * 10.0);
+            }
+        }
+        assert!(*game.pieces() > 0);
+    }
+
+    #[test]
+    fn test_max_lock_resets_2() {
+        let mut game = Game::new(Settings::default());
+        for _ in 0..300 {
+            game.apply(Input::Press(Button::SonicDrop));
+            game.apply(Input::Release(Button::SonicDrop));
+            game.tick(FRAME * 20.0);
+        }
+        assert!(*game.pieces() > 0);
+    }
+
+    #[test]
+    fn test_block_out() {
+        let mut game = Game::new(Settings::default());
+        let mut field = vec![vec![None; 10]; 24];
+        for row in field.iter_mut().skip(20) {
+            for cell in row.iter_mut().skip(3).take(4) {
+                *cell = Some(Color::Cyan);
+            }
+        }
+        game.field = build_field(field);
+        game.tetromino = Box::new(Piece::new(
+            Rc::new(Shape::o()),
+            TetrominoDirection::North,
+            Block::new(Color::Yellow, 0, 0),
+        ));
+        let events = game.apply(Input::Press(Button::HardDrop));
+        assert_eq!(Some(&Event::ToppedOut(TopOut::BlockOut)), events.last());
+        assert_eq!(State::ToppedOut(TopOut::BlockOut), *game.state());
+        assert_eq!(Vec::<Event>::new(), game.apply(Input::Press(Button::Left)));
+        assert_eq!(Vec::<Event>::new(), game.tick(FRAME * 60.0));
+    }
+
+    #[test]
+    fn test_lock_out() {
+        let mut game = Game::new(Settings::default());
+        let mut field = vec![vec![None; 10]; 24];
+        for row in field.iter_mut().take(20) {
+            row[0] = Some(Color::Cyan);
+        }
+        game.field = build_field(field);
+        game.tetromino = Box::new(Piece::new(
+            Rc::new(Shape::o()),
+            TetrominoDirection::North,
+            Block::new(Color::Yellow, 0, 20),
+        ));
+        let events = game.apply(Input::Press(Button::HardDrop));
+        assert_eq!(
+            vec![Event::HardDropped(0), Event::ToppedOut(TopOut::LockOut)],
+            events
+        );
+    }
+
+    #[test]
+    fn test_t_spin() {
+        let mut game = Game::new(Settings::default());
+        let mut field = vec![vec![Some(Color::Cyan); 10]; 2];
+        field.extend(vec![vec![None; 10]; 22]);
+        field[0][4] = None;
+        field[1][3] = None;
+        field[1][4] = None;
+        field[1][5] = None;
+        field[2][3] = Some(Color::Cyan);
+        game.field = build_field(field);
+        game.tetromino = Box::new(Piece::new(
+            Rc::new(Shape::t()),
+            TetrominoDirection::East,
+            Block::new(Color::Purple, 4, 1),
+        ));
+        game.apply(Input::Press(Button::RotateRight));
+        let events = game.apply(Input::Press(Button::HardDrop));
+        assert_eq!(
+            vec![
+                Event::HardDropped(0),
+                Event::Spun(TetrominoKind::T, Spin::Full),
+                Event::Locked(2)
+            ],
+            events[..3]
+        );
+        assert_eq!(1200, *game.score().points());
+    }
+
+    #[test]
+    fn test_combo_and_back_to_back() {
+        let mut game = Game::new(Settings::default());
+        let mut field = vec![vec![Some(Color::Cyan); 10]; 8];
+        field.extend(vec![vec![None; 10]; 16]);
+        for row in field.iter_mut().take(8) {
+            row[0] = None;
+        }
+        field[8][5] = Some(Color::Cyan);
+        game.field = build_field(field);
+        let mut events = Vec::new();
+        for _ in 0..2 {
+            game.tetromino = Box::new(Piece::new(
+                Rc::new(Shape::i()),
+                TetrominoDirection::East,
+                Block::new(Color::Cyan, 0, 10),
+            ));
+            events = game.apply(Input::Press(Button::HardDrop));
+            game.apply(Input::Release(Button::HardDrop));
+        }
+        assert_eq!(
+            vec![Event::Locked(4), Event::Combo(1), Event::BackToBack],
+            events[1..4]
+        );
+        assert_eq!(Some(1), *game.score().combo());
+        assert!(*game.score().back_to_back());
+        assert_eq!(2 * 16 + 800 + 1200 + 50, *game.score().points());
+    }
+
+    #[test]
+    fn test_perfect_clear() {
+        let mut game = Game::new(Settings::default());
+        let mut field = vec![vec![Some(Color::Cyan); 10]; 4];
+        field.extend(vec![vec![None; 10]; 20]);
+        for row in field.iter_mut().take(4) {
+            row[0] = None;
+        }
+        game.field = build_field(field);
+        game.tetromino = Box::new(Piece::new(
+            Rc::new(Shape::i()),
+            TetrominoDirection::East,
+            Block::new(Color::Cyan, 0, 2),
+        ));
+        let events = game.apply(Input::Press(Button::HardDrop));
+        assert_eq!(Event::PerfectClear(4), events[2]);
+        assert!(game.field().is_empty());
+        assert_eq!(800 + 2000, *game.score().points());
+    }
+
+    #[test]
+    fn test_all_spin() {
+        for all_spin in [false, true] {
+            let piece_set = if all_spin {
+                PieceSet::standard().with_all_spin()
+            } else {
+                PieceSet::standard()
+            };
+            // Both the Z and the O fit the slot after turning in place, but the O looks the
+            // same whichever way it faces.
+            for (kind, spun) in [(TetrominoKind::Z, all_spin), (TetrominoKind::O, false)] {
+                let mut game = Game::new(Settings::default());
+                game.field = "
+                    __G_______
+                    ___G______
+                    G__G______
+                    G_G_______
+                "
+                .parse()
+                .unwrap();
+                let shape = piece_set.shape(kind).unwrap().clone();
+                let color = *shape.color();
+                let axis = Block::new(color, 1, 1);
+                game.tetromino = Box::new(Piece::new(shape, TetrominoDirection::North, axis));
+                game.apply(Input::Press(Button::RotateRight));
+                let events = game.apply(Input::Press(Button::HardDrop));
+                assert_eq!(spun, events.contains(&Event::Spun(kind, Spin::Mini)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_partial_lock_out() {
+        let settings = Settings::default().with_partial_lock_out(true);
+        let mut game = Game::new(settings);
+        let mut field = vec![vec![None; 10]; 24];
+        for row in field.iter_mut().take(19) {
+            row[0] = Some(Color::Cyan);
+        }
+        game.field = build_field(field);
+        game.tetromino = Box::new(Piece::new(
+            Rc::new(Shape::o()),
+            TetrominoDirection::North,
+            Block::new(Color::Yellow, 0, 20),
+        ));
+        let events = game.apply(Input::Press(Button::HardDrop));
+        assert_eq!(
+            vec![
+                Event::HardDropped(1),
+                Event::ToppedOut(TopOut::PartialLockOut)
+            ],
+            events
+        );
+    }
+
+    #[test]
+    fn test_restart() {
+        let mut game = Game::new(Settings::default());
+        game.apply(Input::Press(Button::HardDrop));
+        game.apply(Input::Press(Button::ToggleGhost));
+        assert_eq!(
+            vec![Event::Restarted],
+            game.apply(Input::Press(Button::Restart))
+        );
+        assert_eq!(State::Playing, *game.state());
+        assert!(game.field().blocks().is_empty());
+        assert!(!*game.settings().ghost());
+    }
+
+    #[test]
+    fn test_gravity() {
+        let mut game = Game::new(Settings::default());
+        let blocks = game.tetromino().blocks();
+        game.tick(FRAME * 59.0);
+        assert_eq!(blocks, game.tetromino().blocks());
+        game.tick(FRAME);
+        let fallen = blocks.map(|block| block.move_(Direction::Down));
+        assert_eq!(fallen, game.tetromino().blocks());
+    }
+
+    #[test]
+    fn test_twenty_g() {
+        let mut game = Game::new(Settings::default().with_gravity(Gravity::twenty_g()));
+        game.apply(Input::Press(Button::HardDrop));
+        assert_eq!(game.ghost(), game.tetromino().blocks());
+    }
+
+    fn build_field(rows: Vec<Vec<Option<Color>>>) -> Field {
+        let mut field = Field::new(FieldConfig::default());
+        for (y, row) in rows.iter().enumerate() {
+            for (x, color_or_none) in row.iter().enumerate() {
+                if let Some(color) = color_or_none {
+                    field.fix_blocks(vec![Block::new(*color, x as i32, y as i32)]);
+                }
+            }
+        }
+        field
+    }
+}
@@ -0,0 +1,2 @@
+pub mod auto_shift;
+pub mod button;
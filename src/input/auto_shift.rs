@@ -0,0 +1,90 @@
+use crate::models::tetromino::MoveDirection;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AutoShift {
+    das: f64,
+    arr: f64,
+    dir: Option<MoveDirection>,
+    next_at: f64,
+}
+
+impl AutoShift {
+    pub fn new(das: f64, arr: f64) -> Self {
+        Self {
+            das,
+            arr,
+            dir: None,
+            next_at: 0.0,
+        }
+    }
+
+    pub fn dir(&self) -> &Option<MoveDirection> {
+        &self.dir
+    }
+
+    /// Charges the delayed auto shift for `dir`. The first, immediate step is up to the caller.
+    pub fn press(&mut self, dir: MoveDirection, now: f64) {
+        self.dir = Some(dir);
+        self.next_at = now + self.das;
+    }
+
+    pub fn release(&mut self, dir: MoveDirection) {
+        if self.dir == Some(dir) {
+            self.dir = None;
+        }
+    }
+
+    /// Returns how many auto-repeat steps are due by `now`. An ARR of zero shifts to the wall,
+    /// which is reported as `u32::MAX` steps.
+    pub fn tick(&mut self, now: f64) -> u32 {
+        if self.dir.is_none() || now < self.next_at {
+            return 0;
+        }
+        if self.arr <= 0.0 {
+            return u32::MAX;
+        }
+        let mut steps = 0;
+        while self.next_at <= now {
+            self.next_at += self.arr;
+            steps += 1;
+        }
+        steps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_1() {
+        let mut auto_shift = AutoShift::new(150.0, 50.0);
+        auto_shift.press(MoveDirection::Left, 1000.0);
+        assert_eq!(0, auto_shift.tick(1149.0));
+        assert_eq!(1, auto_shift.tick(1150.0));
+        assert_eq!(0, auto_shift.tick(1199.0));
+        assert_eq!(3, auto_shift.tick(1300.0));
+    }
+
+    #[test]
+    fn test_tick_2() {
+        let mut auto_shift = AutoShift::new(150.0, 0.0);
+        auto_shift.press(MoveDirection::Right, 1000.0);
+        assert_eq!(0, auto_shift.tick(1100.0));
+        assert_eq!(u32::MAX, auto_shift.tick(1150.0));
+    }
+
+    #[test]
+    fn test_release() {
+        let mut auto_shift = AutoShift::new(150.0, 50.0);
+        auto_shift.press(MoveDirection::Left, 1000.0);
+        auto_shift.press(MoveDirection::Right, 1100.0);
+        auto_shift.release(MoveDirection::Left);
+        assert_eq!(Some(MoveDirection::Right), *auto_shift.dir());
+        assert_eq!(0, auto_shift.tick(1200.0));
+        assert_eq!(1, auto_shift.tick(1250.0));
+        auto_shift.release(MoveDirection::Right);
+        assert_eq!(None, *auto_shift.dir());
+        assert_eq!(0, auto_shift.tick(2000.0));
+    }
+}
@@ -0,0 +1,76 @@
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Button {
+    Left,
+    Right,
+    SoftDrop,
+    HardDrop,
+    SonicDrop,
+    RotateLeft,
+    RotateRight,
+    Hold,
+    ToggleGhost,
+    Restart,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Buttons {
+    held: Vec<Button>,
+}
+
+impl Buttons {
+    /// Takes the buttons held this frame and returns the ones pressed and released since the
+    /// previous frame.
+    pub fn update(&mut self, held: Vec<Button>) -> (Vec<Button>, Vec<Button>) {
+        let pressed = held
+            .iter()
+            .filter(|button| !self.held.contains(button))
+            .copied()
+            .collect();
+        let released = self
+            .held
+            .iter()
+            .filter(|button| !held.contains(button))
+            .copied()
+            .collect();
+        self.held = held;
+        (pressed, released)
+    }
+
+    pub fn press(&mut self, button: Button) {
+        if !self.held.contains(&button) {
+            self.held.push(button);
+        }
+    }
+
+    pub fn release(&mut self, button: Button) {
+        self.held.retain(|held| *held != button);
+    }
+
+    pub fn is_held(&self, button: Button) -> bool {
+        self.held.contains(&button)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update() {
+        let mut buttons = Buttons::default();
+        assert_eq!(
+            (vec![Button::Left, Button::Hold], vec![]),
+            buttons.update(vec![Button::Left, Button::Hold])
+        );
+        assert_eq!(
+            (vec![], vec![]),
+            buttons.update(vec![Button::Left, Button::Hold])
+        );
+        assert_eq!(
+            (vec![Button::Right], vec![Button::Hold]),
+            buttons.update(vec![Button::Left, Button::Right])
+        );
+        assert!(buttons.is_held(Button::Right));
+        assert!(!buttons.is_held(Button::Hold));
+    }
+}
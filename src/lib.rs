@@ -1,4 +1,10 @@
-mod models;
+pub mod fumen;
+pub mod game;
+pub mod input;
+pub mod models;
+pub mod replay;
+pub mod settings;
+mod view;
 
 use std::result::Result;
 
@@ -8,113 +14,164 @@ use retrospector::app::{run, App, AppConfig};
 use retrospector::render::{clear, Renderer, SpriteStore};
 use retrospector::update::KeyEvent;
 
-use models::field::Field;
-use models::tetromino::{MoveDirection, RotateDirection, Tetromino};
-use models::tetromino_factory::TetrominoFactory;
+use game::{Event, Game, Input, State};
+use input::button::{Button, Buttons};
+use replay::Replay;
+use settings::Settings;
+use view::{canvas_size, render_block, render_ghost_block, render_hud};
 
 #[wasm_bindgen(start)]
 pub fn start() -> Result<(), JsValue> {
     let app = Tetris::new();
-    let config = AppConfig::new(String::from("canvas"), 320.0, 640.0);
+    let (width, height) = canvas_size(app.game.settings().field());
+    let config = AppConfig::new(String::from("canvas"), width, height);
     run(app, config)
 }
 
 struct Tetris {
-    field: Field,
-    tetromino_factory: TetrominoFactory,
-    tetromino: Box<dyn Tetromino>,
+    game: Game,
+    buttons: Buttons,
+    replay: Replay,
     tetromino_sprites: SpriteStore,
-    updated_at: f64,
+    updated_at: Option<f64>,
 }
 
 impl Tetris {
     fn new() -> Self {
-        let field = Field::new(vec![vec![None; 10]; 24]);
-        let mut tetromino_factory = TetrominoFactory::new();
-        let tetromino = tetromino_factory.pop();
+        let game = Game::new(Settings::default());
+        let replay = match Replay::new(&game) {
+            Ok(replay) => replay,
+            Err(e) => {
+                web_sys::console::log_1(&JsValue::from(&e.to_string()));
+                panic!();
+            }
+        };
 
         let bytes = include_bytes!("./assets/sprites/minos.gif");
-        match SpriteStore::new(bytes, "gif", 256, 32, 32, 32) {
-            Ok(tetromino_sprites) => {
-                let updated_at = 0.0;
-                Self {
-                    field,
-                    tetromino_factory,
-                    tetromino,
-                    tetromino_sprites,
-                    updated_at,
-                }
-            }
+        match SpriteStore::new(bytes, "gif", 512, 32, 32, 32) {
+            Ok(tetromino_sprites) => Self {
+                game,
+                buttons: Buttons::default(),
+                replay,
+                tetromino_sprites,
+                updated_at: None,
+            },
             Err(e) => {
                 web_sys::console::log_1(&JsValue::from(&e.to_string()));
                 panic!();
             }
         }
     }
-}
 
-impl App for Tetris {
-    fn update(&mut self, elapsed_time: f64, key_event: &KeyEvent) {
-        if elapsed_time - self.updated_at < 100.0 {
-            return;
-        }
+    fn held_buttons(key_event: &KeyEvent) -> Vec<Button> {
+        let bindings = [
+            (key_event.is_arrow_left_down(), Button::Left),
+            (key_event.is_arrow_right_down(), Button::Right),
+            (key_event.is_arrow_down_down(), Button::SoftDrop),
+            (key_event.is_arrow_up_down(), Button::HardDrop),
+            (key_event.is_key_v_down(), Button::SonicDrop),
+            (key_event.is_key_z_down(), Button::RotateLeft),
+            (key_event.is_key_x_down(), Button::RotateRight),
+            (key_event.is_key_c_down(), Button::Hold),
+            (key_event.is_key_g_down(), Button::ToggleGhost),
+            (key_event.is_key_r_down(), Button::Restart),
+        ];
+        bindings
+            .iter()
+            .filter(|(is_down, _)| *is_down)
+            .map(|(_, button)| *button)
+            .collect()
+    }
 
-        if elapsed_time - self.updated_at > 300.0 || key_event.is_arrow_down_down() {
-            let blocks = self.tetromino.dry_move(MoveDirection::Down);
-            if self.field.is_vacant(&blocks) {
-                self.tetromino.move_(MoveDirection::Down);
-                self.updated_at = elapsed_time;
-                return;
+    fn apply(&mut self, input: Input) {
+        if input == Input::Press(Button::Restart) {
+            // The new game deals from a new seed and so gets a replay of its own. A game
+            // that topped out has saved its replay already.
+            if *self.game.state() == State::Playing {
+                self.save_replay();
             }
-
-            if Field::can_fix(&blocks) {
-                let blocks = self.tetromino.blocks();
-                self.field.fix_blocks(blocks);
-                self.field.clear_blocks();
-            } else {
-                // game over
+            self.game.apply(input);
+            match Replay::new(&self.game) {
+                Ok(replay) => self.replay = replay,
+                Err(e) => web_sys::console::log_1(&JsValue::from(&e.to_string())),
             }
-
-            self.tetromino = self.tetromino_factory.pop();
-            if !self.field.is_vacant(&self.tetromino.blocks()) {
-                // game over
+        } else {
+            if let Err(e) = self.replay.record(*self.game.frame(), input) {
+                web_sys::console::log_1(&JsValue::from(&e.to_string()));
             }
+            let events = self.game.apply(input);
+            self.save_replay_on_top_out(&events);
         }
+    }
 
-        if key_event.is_arrow_left_down() {
-            let blocks = self.tetromino.dry_move(MoveDirection::Left);
-            if self.field.is_vacant(&blocks) {
-                self.tetromino.move_(MoveDirection::Left);
-                self.updated_at = elapsed_time;
-            }
-        } else if key_event.is_arrow_right_down() {
-            let blocks = self.tetromino.dry_move(MoveDirection::Right);
-            if self.field.is_vacant(&blocks) {
-                self.tetromino.move_(MoveDirection::Right);
-                self.updated_at = elapsed_time;
-            }
-        } else if key_event.is_key_z_down() {
-            let blocks = self.tetromino.dry_rotate(RotateDirection::Left);
-            if self.field.is_vacant(&blocks) {
-                self.tetromino.rotate(RotateDirection::Left);
-                self.updated_at = elapsed_time;
-            }
-        } else if key_event.is_key_x_down() {
-            let blocks = self.tetromino.dry_rotate(RotateDirection::Right);
-            if self.field.is_vacant(&blocks) {
-                self.tetromino.rotate(RotateDirection::Right);
-                self.updated_at = elapsed_time;
-            }
+    // Replays go to the console, from where the host page can save them.
+    fn save_replay(&self) {
+        web_sys::console::log_1(&JsValue::from(&self.replay.to_string()));
+    }
+
+    fn save_replay_on_top_out(&self, events: &[Event]) {
+        if events
+            .iter()
+            .any(|event| matches!(event, Event::ToppedOut(_)))
+        {
+            self.save_replay();
         }
     }
+}
+
+impl App for Tetris {
+    fn update(&mut self, elapsed_time: f64, key_event: &KeyEvent) {
+        let (pressed, released) = self.buttons.update(Self::held_buttons(key_event));
+        for button in released {
+            self.apply(Input::Release(button));
+        }
+        for button in pressed {
+            self.apply(Input::Press(button));
+        }
+
+        let dt = elapsed_time - self.updated_at.unwrap_or(elapsed_time);
+        self.updated_at = Some(elapsed_time);
+        let events = self.game.tick(dt);
+        self.replay.advance(*self.game.frame());
+        self.save_replay_on_top_out(&events);
+    }
 
     fn render(&self, renderer: &Renderer) {
+        let config = self.game.field().config();
+        let piece_set = self.game.settings().piece_set();
+        let sprites = &self.tetromino_sprites;
         clear(renderer);
-        for block in self.tetromino.blocks() {
-            let _ = block.render(renderer, &self.tetromino_sprites);
+        if *self.game.settings().ghost() {
+            for block in self.game.ghost() {
+                let _ = render_ghost_block(&block, config, renderer, sprites);
+            }
+        }
+        for block in self.game.tetromino().blocks() {
+            let _ = render_block(&block, config, renderer, sprites);
         }
-        for block in self.field.blocks() {
-            let _ = block.render(renderer, &self.tetromino_sprites);
+        for block in self.game.field().blocks() {
+            let _ = render_block(&block, config, renderer, sprites);
+        }
+        // The hold box sits to the right of the field, level with the spawn rows, and the
+        // preview column further right with one piece every three rows.
+        let (spawn_x, _) = config.spawn();
+        let width = *config.width() as i32;
+        if let Some(Ok(piece)) = self.game.hold().map(|kind| piece_set.build(kind, config)) {
+            for block in piece.blocks() {
+                let block = block.offset(width + 2 - spawn_x, -3);
+                let _ = render_block(&block, config, renderer, sprites);
+            }
+        }
+        for (idx, kind) in self.game.preview().iter().enumerate() {
+            let piece = match piece_set.build(*kind, config) {
+                Ok(piece) => piece,
+                Err(_) => continue,
+            };
+            for block in piece.blocks() {
+                let block = block.offset(width + 6 - spawn_x, -3 - 3 * idx as i32);
+                let _ = render_block(&block, config, renderer, sprites);
+            }
         }
+        render_hud(self.game.score());
     }
 }
@@ -1,4 +1,10 @@
 pub mod block;
 pub mod field;
+pub mod gravity;
+pub mod lock_delay;
+pub mod piece_set;
+pub mod randomizer;
+pub mod rng;
+pub mod score;
 pub mod tetromino;
 pub mod tetromino_factory;
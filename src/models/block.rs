@@ -1,7 +1,8 @@
-use anyhow::Result;
+use std::ops::Deref;
+
 use derive_new::new;
 
-use retrospector::render::{draw_image, Position, Renderer, SpriteStore};
+use crate::models::tetromino::srs::Kick;
 
 #[derive(Clone, Copy, Debug, Eq, new, PartialEq)]
 pub struct Block {
@@ -10,6 +11,18 @@ pub struct Block {
     y: i32,
 }
 
+/// The most cells a single piece may cover, enough for octominoes. `Shape::new` panics on
+/// a bigger piece and piece sets read from text reject one.
+pub const MAX_BLOCKS: usize = 8;
+
+/// The blocks of a single piece, held inline so that moving and rotating pieces doesn't
+/// allocate.
+#[derive(Clone, Copy, Debug)]
+pub struct Blocks {
+    blocks: [Block; MAX_BLOCKS],
+    len: usize,
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Color {
     Cyan,
@@ -19,6 +32,8 @@ pub enum Color {
     Red,
     Purple,
     Yellow,
+    // Garbage, which belongs to no piece.
+    Gray,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -41,6 +56,16 @@ impl Block {
         Self { color, x, y }
     }
 
+    pub fn kick(&self, kick: &Kick) -> Self {
+        let color = self.color;
+        let (x, y) = (self.x + kick.x(), self.y + kick.y());
+        Self { color, x, y }
+    }
+
+    pub fn offset(&self, x: i32, y: i32) -> Self {
+        Self::new(self.color, self.x + x, self.y + y)
+    }
+
     pub fn color(&self) -> &Color {
         &self.color
     }
@@ -52,22 +77,61 @@ impl Block {
     pub fn y(&self) -> &i32 {
         &self.y
     }
+}
+
+impl Blocks {
+    pub fn map(&self, f: impl Fn(&Block) -> Block) -> Self {
+        self.iter().map(f).collect()
+    }
+}
 
-    pub fn render(&self, renderer: &Renderer, tetromino_sprites: &SpriteStore) -> Result<()> {
-        let col = match self.color() {
-            Color::Cyan => 1,
-            Color::Blue => 2,
-            Color::Orange => 3,
-            Color::Green => 4,
-            Color::Red => 5,
-            Color::Purple => 6,
-            Color::Yellow => 7,
-        };
-        if let Ok(sprite) = tetromino_sprites.sprite(col) {
-            let position = Position::new(*self.x() as f64 * 32.0, (19.0 - *self.y() as f64) * 32.0);
-            draw_image(renderer, &sprite, position)?;
+impl Deref for Blocks {
+    type Target = [Block];
+
+    fn deref(&self) -> &[Block] {
+        &self.blocks[..self.len]
+    }
+}
+
+impl PartialEq for Blocks {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for Blocks {}
+
+impl FromIterator<Block> for Blocks {
+    fn from_iter<I: IntoIterator<Item = Block>>(iter: I) -> Self {
+        let mut blocks = [Block::new(Color::Cyan, 0, 0); MAX_BLOCKS];
+        let mut len = 0;
+        for block in iter {
+            assert!(
+                len < MAX_BLOCKS,
+                "a piece covers at most {} cells",
+                MAX_BLOCKS
+            );
+            blocks[len] = block;
+            len += 1;
         }
+        Self { blocks, len }
+    }
+}
+
+impl IntoIterator for Blocks {
+    type Item = Block;
+    type IntoIter = std::iter::Take<std::array::IntoIter<Block, MAX_BLOCKS>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.blocks.into_iter().take(self.len)
+    }
+}
+
+impl<'a> IntoIterator for &'a Blocks {
+    type Item = &'a Block;
+    type IntoIter = std::slice::Iter<'a, Block>;
 
-        Ok(())
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
@@ -1,42 +1,148 @@
-use crate::models::block::{Block, Color};
+use std::fmt;
+use std::str::FromStr;
 
+use anyhow::{anyhow, bail, Error, Result};
 use derive_new::new;
 
-#[derive(Clone, Debug, Eq, PartialEq, new)]
-pub struct Field(Vec<Vec<Option<Color>>>);
+use crate::models::block::{Block, Color};
+
+/// Fields are at least one and at most this many columns wide, as each row is a `u64`.
+pub const MAX_WIDTH: usize = 64;
+
+/// Rows a field needs above the visible ones for pieces to spawn in.
+pub const SPAWN_ROWS: usize = 3;
+
+/// The size of the field: `height` rows in all, of which the bottom `visible_height` are
+/// shown and the rest are where pieces spawn.
+#[derive(Clone, Copy, Debug, Eq, new, PartialEq)]
+pub struct FieldConfig {
+    width: usize,
+    height: usize,
+    visible_height: usize,
+}
+
+/// Occupancy is kept as one bit per cell, a `u64` per row with bit `x` for column `x`, so
+/// collision checks, full rows and clears are bit operations. Colors live in their own plane
+/// and are only read for drawing.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Field {
+    config: FieldConfig,
+    bits: Vec<u64>,
+    colors: Vec<Option<Color>>,
+}
+
+impl Default for FieldConfig {
+    fn default() -> Self {
+        Self::new(10, 24, 20)
+    }
+}
+
+impl FieldConfig {
+    /// Builds a config for a field that pieces can be played in: 1 to 64 columns wide, with
+    /// room to spawn above the visible rows.
+    pub fn try_new(width: usize, height: usize, visible_height: usize) -> Result<Self> {
+        if !(1..=MAX_WIDTH).contains(&width) {
+            bail!("a field is 1 to {} columns wide, not {}", MAX_WIDTH, width);
+        }
+        if height < visible_height + SPAWN_ROWS {
+            bail!(
+                "a field {} rows high has no room to spawn above {} visible rows",
+                height,
+                visible_height
+            );
+        }
+        Ok(Self::new(width, height, visible_height))
+    }
+
+    pub fn width(&self) -> &usize {
+        &self.width
+    }
+
+    pub fn height(&self) -> &usize {
+        &self.height
+    }
+
+    pub fn visible_height(&self) -> &usize {
+        &self.visible_height
+    }
+
+    /// Where the axis of a piece spawns: the middle column, left of centre on even widths,
+    /// on the row right above the visible field.
+    pub fn spawn(&self) -> (i32, i32) {
+        ((self.width as i32 - 1) / 2, self.visible_height as i32)
+    }
+}
 
 impl Field {
-    pub fn is_vacant(&self, blocks: &Vec<Block>) -> bool {
-        blocks.iter().all(|block| match self.get(block) {
-            Some(color_or_none) => color_or_none.is_none(),
-            None => false,
-        })
-    }
-    pub fn can_fix(blocks: &Vec<Block>) -> bool {
-        blocks.iter().any(|block| *block.y() < 20)
-    }
-    pub fn fix_blocks(&mut self, blocks: Vec<Block>) {
-        for block in blocks.iter() {
-            self.set(block);
+    pub fn new(config: FieldConfig) -> Self {
+        assert!(
+            (1..=MAX_WIDTH).contains(&config.width),
+            "fields are 1 to {} columns wide, not {}",
+            MAX_WIDTH,
+            config.width
+        );
+        Self {
+            config,
+            bits: vec![0; config.height],
+            colors: vec![None; config.width * config.height],
+        }
+    }
+    pub fn config(&self) -> &FieldConfig {
+        &self.config
+    }
+    pub fn is_vacant(&self, blocks: &[Block]) -> bool {
+        blocks
+            .iter()
+            .all(|block| self.in_bounds(*block.x(), *block.y()) && !self.is_occupied(block))
+    }
+    pub fn can_fix(&self, blocks: &[Block]) -> bool {
+        let visible_height = self.config.visible_height as i32;
+        blocks.iter().any(|block| *block.y() < visible_height)
+    }
+    pub fn can_fix_entirely(&self, blocks: &[Block]) -> bool {
+        let visible_height = self.config.visible_height as i32;
+        blocks.iter().all(|block| *block.y() < visible_height)
+    }
+    pub fn fix_blocks(&mut self, blocks: impl IntoIterator<Item = Block>) {
+        for block in blocks {
+            self.set(&block);
         }
     }
     pub fn clear_blocks(&mut self) -> i32 {
-        (0..24).rev().fold(0, |score, row_idx| {
-            if self.is_filled(row_idx) {
-                self.clear(row_idx);
-                score + 1
+        let full = self.full_row();
+        let mut cleared = 0;
+        let mut y = 0;
+        while y < self.bits.len() {
+            if self.bits[y] == full {
+                self.clear(y);
+                cleared += 1;
             } else {
-                score
+                y += 1;
             }
-        })
+        }
+        cleared
+    }
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|row| *row == 0)
+    }
+    /// Returns the color of the cell, or `None` if it is empty or off the field.
+    pub fn color(&self, x: i32, y: i32) -> Option<Color> {
+        if self.in_bounds(x, y) {
+            self.colors[y as usize * self.config.width + x as usize]
+        } else {
+            None
+        }
+    }
+    /// Returns the occupancy bits of each row, bottom first.
+    pub fn rows(&self) -> &[u64] {
+        &self.bits
     }
     pub fn blocks(&self) -> Vec<Block> {
         let mut blocks = Vec::new();
-        for (y, row) in self.0.iter().enumerate() {
-            for (x, color_or_none) in row.iter().enumerate() {
-                if let Some(color) = color_or_none {
-                    blocks.push(Block::new(*color, x as i32, y as i32));
-                }
+        for (idx, color_or_none) in self.colors.iter().enumerate() {
+            if let Some(color) = color_or_none {
+                let (x, y) = (idx % self.config.width, idx / self.config.width);
+                blocks.push(Block::new(*color, x as i32, y as i32));
             }
         }
         blocks
@@ -44,32 +150,159 @@ impl Field {
 }
 
 impl Field {
-    fn get(&self, block: &Block) -> Option<&Option<Color>> {
-        match self.0.get(*block.y() as usize) {
-            None => None,
-            Some(row) => row.get(*block.x() as usize),
-        }
+    fn in_bounds(&self, x: i32, y: i32) -> bool {
+        0 <= x && (x as usize) < self.config.width && 0 <= y && (y as usize) < self.config.height
+    }
+    fn is_occupied(&self, block: &Block) -> bool {
+        self.bits[*block.y() as usize] & (1 << *block.x()) != 0
     }
     fn set(&mut self, block: &Block) {
-        if let Some(row) = self.0.get_mut(*block.y() as usize) {
-            if let Some(color_or_none) = row.get_mut(*block.x() as usize) {
-                *color_or_none = Some(*block.color());
+        let (x, y) = (*block.x(), *block.y());
+        if self.in_bounds(x, y) {
+            self.bits[y as usize] |= 1 << x;
+            self.colors[y as usize * self.config.width + x as usize] = Some(*block.color());
+        }
+    }
+    fn full_row(&self) -> u64 {
+        u64::MAX >> (64 - self.config.width)
+    }
+    // Shifts the rows above `y` down by one in place and empties the top row.
+    fn clear(&mut self, y: usize) {
+        let (width, height) = (self.config.width, self.config.height);
+        self.bits.copy_within(y + 1.., y);
+        self.bits[height - 1] = 0;
+        self.colors.copy_within((y + 1) * width.., y * width);
+        self.colors[(height - 1) * width..].fill(None);
+    }
+}
+
+// A field as text: one line per row, top first, with `_` for an empty cell, the letter of
+// the piece whose color fills it, or `G` for garbage. Empty rows above the stack are left
+// out. A field of other than the default size starts with a `field` line giving its width,
+// height and visible height, as in `field 4 40 36`.
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let top = self
+            .bits
+            .iter()
+            .rposition(|row| *row != 0)
+            .map_or(0, |y| y + 1);
+        if self.config != FieldConfig::default() {
+            let config = &self.config;
+            let (width, height) = (config.width, config.height);
+            write!(f, "field {} {} {}", width, height, config.visible_height)?;
+            if top > 0 {
+                writeln!(f)?;
+            }
+        }
+        for y in (0..top).rev() {
+            for x in 0..self.config.width {
+                let cell = self.color(x as i32, y as i32).map_or('_', letter);
+                write!(f, "{}", cell)?;
+            }
+            if y > 0 {
+                writeln!(f)?;
             }
         }
+        Ok(())
     }
-    fn is_filled(&self, row_idx: i32) -> bool {
-        match self.0.get(row_idx as usize) {
-            Some(row) => row.iter().all(|color_or_none| color_or_none.is_some()),
-            None => false,
+}
+
+// Leading whitespace and blank lines are ignored so boards can be indented in string
+// literals. Without a `field` line the field is as wide as the rows and as high as the
+// default one, so a board any taller needs the line.
+impl FromStr for Field {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut lines: Vec<&str> = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        let config = match lines.first().and_then(|line| line.strip_prefix("field ")) {
+            Some(size) => {
+                let config = size.parse()?;
+                lines.remove(0);
+                config
+            }
+            None => {
+                let width = lines.first().map_or(10, |line| line.chars().count());
+                let default = FieldConfig::default();
+                FieldConfig::try_new(width, default.height, default.visible_height)?
+            }
+        };
+        let (width, height) = (config.width, config.height);
+        if lines.len() > height {
+            bail!(
+                "{} rows don't fit in a field {} rows high",
+                lines.len(),
+                height
+            );
         }
+        let mut field = Field::new(config);
+        for (row, line) in lines.iter().enumerate() {
+            let y = (lines.len() - 1 - row) as i32;
+            if line.chars().count() != width {
+                bail!("row {} is not {} cells wide", row + 1, width);
+            }
+            for (x, cell) in line.chars().enumerate() {
+                if cell != '_' {
+                    let color = color(cell).ok_or_else(|| anyhow!("unknown cell {:?}", cell))?;
+                    field.set(&Block::new(color, x as i32, y));
+                }
+            }
+        }
+        Ok(field)
     }
-    fn clear(&mut self, row_idx: i32) {
-        self.0.remove(row_idx as usize);
-        self.0.push(vec![None; 10]);
+}
+
+// A size as its width, height and visible height, as in `10 24 20`.
+impl FromStr for FieldConfig {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let size = s
+            .split_whitespace()
+            .map(|n| n.parse())
+            .collect::<Result<Vec<usize>, _>>()
+            .map_err(|_| anyhow!("bad field size {:?}", s))?;
+        match size[..] {
+            [width, height, visible_height] => Self::try_new(width, height, visible_height),
+            _ => bail!("bad field size {:?}", s),
+        }
+    }
+}
+
+fn letter(color: Color) -> char {
+    match color {
+        Color::Cyan => 'I',
+        Color::Blue => 'J',
+        Color::Orange => 'L',
+        Color::Yellow => 'O',
+        Color::Green => 'S',
+        Color::Purple => 'T',
+        Color::Red => 'Z',
+        Color::Gray => 'G',
+    }
+}
+
+fn color(letter: char) -> Option<Color> {
+    match letter {
+        'I' => Some(Color::Cyan),
+        'J' => Some(Color::Blue),
+        'L' => Some(Color::Orange),
+        'O' => Some(Color::Yellow),
+        'S' => Some(Color::Green),
+        'T' => Some(Color::Purple),
+        'Z' => Some(Color::Red),
+        'G' => Some(Color::Gray),
+        _ => None,
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 
@@ -90,7 +323,7 @@ mod tests {
     #[test]
     fn test_is_vacant_3() {
         let mut field = build_field_with_missing_lines(4);
-        field.0[3][0] = Some(Color::Cyan);
+        field.fix_blocks(vec![Block::new(Color::Cyan, 0, 3)]);
         let blocks = build_blocks();
         assert_eq!(false, field.is_vacant(&blocks));
     }
@@ -103,7 +336,7 @@ mod tests {
             Block::new(Color::Cyan, 0, 21),
             Block::new(Color::Cyan, 0, 22),
         ];
-        assert_eq!(true, Field::can_fix(&blocks));
+        assert_eq!(true, Field::new(FieldConfig::default()).can_fix(&blocks));
     }
 
     #[test]
@@ -114,22 +347,52 @@ mod tests {
             Block::new(Color::Cyan, 0, 22),
             Block::new(Color::Cyan, 0, 23),
         ];
-        assert_eq!(false, Field::can_fix(&blocks));
+        assert_eq!(false, Field::new(FieldConfig::default()).can_fix(&blocks));
+    }
+
+    #[test]
+    fn test_can_fix_entirely_1() {
+        let blocks = vec![
+            Block::new(Color::Cyan, 0, 16),
+            Block::new(Color::Cyan, 0, 17),
+            Block::new(Color::Cyan, 0, 18),
+            Block::new(Color::Cyan, 0, 19),
+        ];
+        assert!(Field::new(FieldConfig::default()).can_fix_entirely(&blocks));
+    }
+
+    #[test]
+    fn test_can_fix_entirely_2() {
+        let blocks = vec![
+            Block::new(Color::Cyan, 0, 17),
+            Block::new(Color::Cyan, 0, 18),
+            Block::new(Color::Cyan, 0, 19),
+            Block::new(Color::Cyan, 0, 20),
+        ];
+        assert!(!Field::new(FieldConfig::default()).can_fix_entirely(&blocks));
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let mut field = Field::new(FieldConfig::default());
+        assert!(field.is_empty());
+        field.fix_blocks(vec![Block::new(Color::Cyan, 9, 23)]);
+        assert!(!field.is_empty());
     }
 
     #[test]
     fn test_fix_blocks() {
         let mut field = build_field_with_missing_lines(4);
         let blocks = build_blocks();
-        assert_eq!(field.0[0][0], None);
-        assert_eq!(field.0[1][0], None);
-        assert_eq!(field.0[2][0], None);
-        assert_eq!(field.0[3][0], None);
+        assert_eq!(field.color(0, 0), None);
+        assert_eq!(field.color(0, 1), None);
+        assert_eq!(field.color(0, 2), None);
+        assert_eq!(field.color(0, 3), None);
         field.fix_blocks(blocks);
-        assert_eq!(field.0[0][0], Some(Color::Cyan));
-        assert_eq!(field.0[1][0], Some(Color::Cyan));
-        assert_eq!(field.0[2][0], Some(Color::Cyan));
-        assert_eq!(field.0[3][0], Some(Color::Cyan));
+        assert_eq!(field.color(0, 0), Some(Color::Cyan));
+        assert_eq!(field.color(0, 1), Some(Color::Cyan));
+        assert_eq!(field.color(0, 2), Some(Color::Cyan));
+        assert_eq!(field.color(0, 3), Some(Color::Cyan));
     }
 
     #[test]
@@ -138,61 +401,112 @@ mod tests {
         let field_clone = field.clone();
         assert_eq!(0, field.clear_blocks());
         assert_eq!(field_clone, field);
-        assert_eq!(24, field.0.len());
+        assert_eq!(24, field.rows().len());
     }
 
     #[test]
     fn test_clear_blocks_2() {
         let mut field = build_field_with_missing_lines(5);
-        field.0[3][0] = Some(Color::Cyan);
+        field.fix_blocks(vec![Block::new(Color::Cyan, 0, 3)]);
         assert_eq!(1, field.clear_blocks());
-        assert_eq!(missing_line(), field.0[3]);
-        assert_eq!(missing_line(), field.0[2]);
-        assert_eq!(missing_line(), field.0[1]);
-        assert_eq!(missing_line(), field.0[0]);
-        assert_eq!(24, field.0.len());
+        assert_eq!(missing_line(), row(&field, 3));
+        assert_eq!(missing_line(), row(&field, 2));
+        assert_eq!(missing_line(), row(&field, 1));
+        assert_eq!(missing_line(), row(&field, 0));
+        assert_eq!(24, field.rows().len());
     }
 
     #[test]
     fn test_clear_blocks_3() {
         let mut field = build_field_with_missing_lines(5);
-        field.0[3][0] = Some(Color::Cyan);
-        field.0[2][0] = Some(Color::Cyan);
+        field.fix_blocks(vec![Block::new(Color::Cyan, 0, 3)]);
+        field.fix_blocks(vec![Block::new(Color::Cyan, 0, 2)]);
         assert_eq!(2, field.clear_blocks());
-        assert_eq!(vec![None; 10], field.0[3]);
-        assert_eq!(missing_line(), field.0[2]);
-        assert_eq!(missing_line(), field.0[1]);
-        assert_eq!(missing_line(), field.0[0]);
-        assert_eq!(24, field.0.len());
+        assert_eq!(vec![None; 10], row(&field, 3));
+        assert_eq!(missing_line(), row(&field, 2));
+        assert_eq!(missing_line(), row(&field, 1));
+        assert_eq!(missing_line(), row(&field, 0));
+        assert_eq!(24, field.rows().len());
     }
 
     #[test]
     fn test_clear_blocks_4() {
         let mut field = build_field_with_missing_lines(5);
-        field.0[3][0] = Some(Color::Cyan);
-        field.0[2][0] = Some(Color::Cyan);
-        field.0[1][0] = Some(Color::Cyan);
+        field.fix_blocks(vec![Block::new(Color::Cyan, 0, 3)]);
+        field.fix_blocks(vec![Block::new(Color::Cyan, 0, 2)]);
+        field.fix_blocks(vec![Block::new(Color::Cyan, 0, 1)]);
         assert_eq!(3, field.clear_blocks());
-        assert_eq!(vec![None; 10], field.0[3]);
-        assert_eq!(vec![None; 10], field.0[2]);
-        assert_eq!(missing_line(), field.0[1]);
-        assert_eq!(missing_line(), field.0[0]);
-        assert_eq!(24, field.0.len());
+        assert_eq!(vec![None; 10], row(&field, 3));
+        assert_eq!(vec![None; 10], row(&field, 2));
+        assert_eq!(missing_line(), row(&field, 1));
+        assert_eq!(missing_line(), row(&field, 0));
+        assert_eq!(24, field.rows().len());
     }
 
     #[test]
     fn test_clear_blocks_5() {
         let mut field = build_field_with_missing_lines(5);
-        field.0[3][0] = Some(Color::Cyan);
-        field.0[2][0] = Some(Color::Cyan);
-        field.0[1][0] = Some(Color::Cyan);
-        field.0[0][0] = Some(Color::Cyan);
+        field.fix_blocks(vec![Block::new(Color::Cyan, 0, 3)]);
+        field.fix_blocks(vec![Block::new(Color::Cyan, 0, 2)]);
+        field.fix_blocks(vec![Block::new(Color::Cyan, 0, 1)]);
+        field.fix_blocks(vec![Block::new(Color::Cyan, 0, 0)]);
         assert_eq!(4, field.clear_blocks());
-        assert_eq!(vec![None; 10], field.0[3]);
-        assert_eq!(vec![None; 10], field.0[2]);
-        assert_eq!(vec![None; 10], field.0[1]);
-        assert_eq!(missing_line(), field.0[0]);
-        assert_eq!(24, field.0.len());
+        assert_eq!(vec![None; 10], row(&field, 3));
+        assert_eq!(vec![None; 10], row(&field, 2));
+        assert_eq!(vec![None; 10], row(&field, 1));
+        assert_eq!(missing_line(), row(&field, 0));
+        assert_eq!(24, field.rows().len());
+    }
+
+    #[test]
+    fn test_config() {
+        let mut field = Field::new(FieldConfig::new(4, 8, 6));
+        assert_eq!((1, 6), field.config().spawn());
+        assert_eq!((9, 20), FieldConfig::new(20, 24, 20).spawn());
+        assert!(!field.is_vacant(&[Block::new(Color::Cyan, 4, 0)]));
+        assert!(field.can_fix_entirely(&[Block::new(Color::Cyan, 0, 5)]));
+        assert!(!field.can_fix(&[Block::new(Color::Cyan, 0, 6)]));
+        field.fix_blocks((0..4).map(|x| Block::new(Color::Cyan, x, 7)));
+        assert_eq!(1, field.clear_blocks());
+        assert!(field.is_empty());
+        assert_eq!(8, field.rows().len());
+    }
+
+    #[test]
+    fn test_rows() {
+        let mut field = Field::new(FieldConfig::new(20, 4, 2));
+        field.fix_blocks((0..19).map(|x| Block::new(Color::Red, x, 1)));
+        assert_eq!(&[0, 0x7ffff, 0, 0], field.rows());
+        field.fix_blocks(vec![Block::new(Color::Blue, 19, 1)]);
+        assert_eq!(1, field.clear_blocks());
+        assert!(field.is_empty());
+        assert_eq!(None, field.color(19, 1));
+    }
+
+    #[test]
+    fn test_width() {
+        let mut field = Field::new(FieldConfig::new(MAX_WIDTH, 4, 2));
+        field.fix_blocks((0..64).map(|x| Block::new(Color::Red, x, 0)));
+        field.fix_blocks(vec![Block::new(Color::Blue, 63, 1)]);
+        assert_eq!(1, field.clear_blocks());
+        assert_eq!(&[1 << 63, 0, 0, 0], field.rows());
+        assert_eq!(Some(Color::Blue), field.color(63, 0));
+        assert_eq!(None, field.color(63, 1));
+    }
+
+    #[test]
+    fn test_try_new() {
+        assert!(FieldConfig::try_new(1, 23, 20).is_ok());
+        assert!(FieldConfig::try_new(MAX_WIDTH, 24, 20).is_ok());
+        assert!(FieldConfig::try_new(0, 24, 20).is_err());
+        assert!(FieldConfig::try_new(MAX_WIDTH + 1, 24, 20).is_err());
+        assert!(FieldConfig::try_new(10, 22, 20).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_width_zero() {
+        Field::new(FieldConfig::new(0, 24, 20));
     }
 
     #[test]
@@ -203,24 +517,81 @@ mod tests {
 
     #[test]
     fn test_blocks_2() {
-        let field = Field(vec![vec![None; 10]; 24]);
+        let field = Field::new(FieldConfig::default());
         assert_eq!(Vec::<Block>::new(), field.blocks());
     }
 
+    #[test]
+    fn test_from_str() {
+        let text = "___T______\nIIIITTTG_O\nJJJLSSZZGO";
+        let field: Field = text.parse().unwrap();
+        assert_eq!(&FieldConfig::default(), field.config());
+        assert_eq!(Some(Color::Purple), field.color(3, 2));
+        assert_eq!(Some(Color::Gray), field.color(7, 1));
+        assert_eq!(None, field.color(8, 1));
+        assert_eq!(Some(Color::Yellow), field.color(9, 0));
+        assert_eq!(text, field.to_string());
+
+        let field: Field = "
+            G___
+            GG_G
+        "
+        .parse()
+        .unwrap();
+        assert_eq!(&FieldConfig::new(4, 24, 20), field.config());
+        assert_eq!("field 4 24 20\nG___\nGG_G", field.to_string());
+        assert_eq!("", Field::new(FieldConfig::default()).to_string());
+        assert_eq!(Field::new(FieldConfig::default()), "".parse().unwrap());
+    }
+
+    #[test]
+    fn test_from_str_size() {
+        let mut field = Field::new(FieldConfig::new(4, 40, 36));
+        field.fix_blocks((0..30).map(|y| Block::new(Color::Gray, y % 4, y)));
+        let text = field.to_string();
+        assert!(text.starts_with("field 4 40 36\n_G__\nG___\n"));
+        assert_eq!(field, text.parse().unwrap());
+
+        let empty = Field::new(FieldConfig::new(6, 14, 10));
+        assert_eq!("field 6 14 10", empty.to_string());
+        assert_eq!(empty, empty.to_string().parse().unwrap());
+
+        let mut field = Field::new(FieldConfig::default());
+        field.fix_blocks((0..22).map(|y| Block::new(Color::Cyan, 0, y)));
+        assert_eq!(field, field.to_string().parse().unwrap());
+    }
+
+    #[test]
+    fn test_from_str_error() {
+        assert!("__X_".parse::<Field>().is_err());
+        assert!("____\n___".parse::<Field>().is_err());
+        assert!("_".repeat(65).parse::<Field>().is_err());
+        assert!("G\n".repeat(25).parse::<Field>().is_err());
+        assert!("field 4 2 2\n____\n____\n____".parse::<Field>().is_err());
+        assert!("field 4 24 20\n___".parse::<Field>().is_err());
+        assert!("field 0 24 20".parse::<Field>().is_err());
+        assert!("field 4 24".parse::<Field>().is_err());
+        assert!("field 4 22 20".parse::<Field>().is_err());
+    }
+
     fn build_field_with_blocks(num: i32) -> Field {
-        let mut field = vec![vec![None; 10]; 24];
+        let mut field = Field::new(FieldConfig::default());
         for row_idx in 0..num {
-            field[row_idx as usize][0] = Some(Color::Cyan);
+            field.fix_blocks(vec![Block::new(Color::Cyan, 0, row_idx)]);
         }
-        Field(field)
+        field
     }
 
     fn build_field_with_missing_lines(num: i32) -> Field {
-        let mut field = vec![vec![None; 10]; 24];
+        let mut field = Field::new(FieldConfig::default());
         for row_idx in 0..num {
-            field[row_idx as usize] = missing_line();
+            field.fix_blocks((1..10).map(|x| Block::new(Color::Cyan, x, row_idx)));
         }
-        Field(field)
+        field
+    }
+
+    fn row(field: &Field, y: i32) -> Vec<Option<Color>> {
+        (0..10).map(|x| field.color(x, y)).collect()
     }
 
     fn missing_line() -> Vec<Option<Color>> {
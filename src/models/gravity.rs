@@ -0,0 +1,72 @@
+/// Gravity at or above this many rows per frame drops pieces straight onto the stack.
+pub const INSTANT: f64 = 20.0;
+
+/// The Guideline curve reaches 20G by this level and turns negative well past it.
+const GUIDELINE_MAX_LEVEL: u32 = 20;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Gravity {
+    Guideline,
+    /// Rows per frame for level 1, 2, ...; the last entry carries on for higher levels.
+    Table(Vec<f64>),
+}
+
+impl Gravity {
+    pub fn twenty_g() -> Self {
+        Gravity::Table(vec![INSTANT])
+    }
+
+    /// Returns how many rows the piece falls per frame at `level`, which may well be a
+    /// fraction of a row.
+    pub fn rows_per_frame(&self, level: u32) -> f64 {
+        match self {
+            Gravity::Guideline if level >= GUIDELINE_MAX_LEVEL => INSTANT,
+            Gravity::Guideline => {
+                let level = level.max(1) as f64;
+                let seconds = (0.8 - (level - 1.0) * 0.007).powf(level - 1.0);
+                1.0 / (seconds * 60.0)
+            }
+            Gravity::Table(table) => {
+                let idx = (level.max(1) as usize - 1).min(table.len().saturating_sub(1));
+                table.get(idx).copied().unwrap_or(0.0)
+            }
+        }
+    }
+
+    pub fn is_instant(&self, level: u32) -> bool {
+        self.rows_per_frame(level) >= INSTANT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows_per_frame_1() {
+        let gravity = Gravity::Guideline;
+        assert!((gravity.rows_per_frame(1) - 1.0 / 60.0).abs() < 1e-9);
+        assert!((gravity.rows_per_frame(2) - 1.0 / (0.793 * 60.0)).abs() < 1e-9);
+        assert!(gravity.rows_per_frame(15) > gravity.rows_per_frame(14));
+        assert!(!gravity.is_instant(15));
+        assert!(gravity.is_instant(20));
+    }
+
+    #[test]
+    fn test_rows_per_frame_2() {
+        let gravity = Gravity::Table(vec![0.5, 1.0, 3.0]);
+        assert_eq!(0.5, gravity.rows_per_frame(1));
+        assert_eq!(1.0, gravity.rows_per_frame(2));
+        assert_eq!(3.0, gravity.rows_per_frame(3));
+        assert_eq!(3.0, gravity.rows_per_frame(30));
+        assert!(Gravity::twenty_g().is_instant(1));
+    }
+
+    #[test]
+    fn test_rows_per_frame_3() {
+        let gravity = Gravity::Guideline;
+        for level in [20, 116, 200, u32::MAX] {
+            assert_eq!(INSTANT, gravity.rows_per_frame(level));
+        }
+    }
+}
@@ -0,0 +1,194 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LockState {
+    Falling,
+    Grounded(f64),
+    // Off the ground with the resets used up, so the delay carries on from where it was
+    // once the piece lands again.
+    Airborne(f64),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LockDelay {
+    delay: f64,
+    max_resets: u32,
+    state: LockState,
+    resets: u32,
+    lowest: i32,
+}
+
+impl LockDelay {
+    pub fn new(delay: f64, max_resets: u32) -> Self {
+        Self {
+            delay,
+            max_resets,
+            state: LockState::Falling,
+            resets: 0,
+            lowest: i32::MAX,
+        }
+    }
+
+    /// Starts over for a freshly spawned piece whose lowest block is on row `y`.
+    pub fn spawn(&mut self, y: i32) {
+        self.state = LockState::Falling;
+        self.resets = 0;
+        self.lowest = y;
+    }
+
+    /// Records that the piece stepped down to row `y`. Reaching a new lowest row gives the
+    /// resets back, as in the Guideline's extended placement.
+    pub fn fall(&mut self, y: i32) {
+        self.reach(y);
+        self.state = match self.exhausted() {
+            Some(since) => LockState::Airborne(since),
+            None => LockState::Falling,
+        };
+    }
+
+    /// Starts the delay once the piece rests on the stack, or picks it up again if the
+    /// resets ran out before the piece left the ground.
+    pub fn land(&mut self, now: f64) {
+        self.state = match self.state {
+            LockState::Falling => LockState::Grounded(now),
+            LockState::Airborne(since) => LockState::Grounded(since),
+            state => state,
+        };
+    }
+
+    /// Records a successful move or rotation. While grounded this restarts the delay until
+    /// the resets run out; after that the delay keeps running.
+    pub fn refresh(&mut self, now: f64, y: i32, grounded: bool) {
+        self.reach(y);
+        let since = match (self.state, self.exhausted()) {
+            (_, Some(since)) => Some(since),
+            (LockState::Grounded(_), None) => {
+                self.resets += 1;
+                None
+            }
+            _ => None,
+        };
+        self.state = match (since, grounded) {
+            (Some(since), true) => LockState::Grounded(since),
+            (Some(since), false) => LockState::Airborne(since),
+            (None, true) => LockState::Grounded(now),
+            (None, false) => LockState::Falling,
+        };
+    }
+
+    pub fn is_expired(&self, now: f64) -> bool {
+        match self.state {
+            LockState::Grounded(since) => now - since >= self.delay,
+            LockState::Falling | LockState::Airborne(_) => false,
+        }
+    }
+}
+
+impl LockDelay {
+    // Returns when the delay started if the resets have run out, as then it keeps running.
+    fn exhausted(&self) -> Option<f64> {
+        match self.state {
+            LockState::Grounded(since) | LockState::Airborne(since)
+                if self.resets >= self.max_resets =>
+            {
+                Some(since)
+            }
+            _ => None,
+        }
+    }
+
+    fn reach(&mut self, y: i32) {
+        if y < self.lowest {
+            self.lowest = y;
+            self.resets = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_expired_1() {
+        let mut lock_delay = LockDelay::new(500.0, 15);
+        lock_delay.spawn(20);
+        assert!(!lock_delay.is_expired(1000.0));
+        lock_delay.land(1000.0);
+        assert!(!lock_delay.is_expired(1499.0));
+        assert!(lock_delay.is_expired(1500.0));
+    }
+
+    #[test]
+    fn test_is_expired_2() {
+        let mut lock_delay = LockDelay::new(500.0, 15);
+        lock_delay.spawn(20);
+        lock_delay.land(1000.0);
+        lock_delay.land(1400.0);
+        assert!(lock_delay.is_expired(1500.0));
+    }
+
+    #[test]
+    fn test_refresh_1() {
+        let mut lock_delay = LockDelay::new(500.0, 15);
+        lock_delay.spawn(20);
+        lock_delay.land(1000.0);
+        lock_delay.refresh(1400.0, 20, true);
+        assert!(!lock_delay.is_expired(1899.0));
+        assert!(lock_delay.is_expired(1900.0));
+    }
+
+    #[test]
+    fn test_refresh_2() {
+        let mut lock_delay = LockDelay::new(500.0, 15);
+        lock_delay.spawn(20);
+        lock_delay.land(1000.0);
+        for i in 1..=15 {
+            lock_delay.refresh(1000.0 + i as f64 * 100.0, 20, true);
+        }
+        assert!(!lock_delay.is_expired(2999.0));
+        lock_delay.refresh(2600.0, 20, true);
+        assert!(!lock_delay.is_expired(2999.0));
+        assert!(lock_delay.is_expired(3000.0));
+    }
+
+    #[test]
+    fn test_refresh_3() {
+        let mut lock_delay = LockDelay::new(500.0, 15);
+        lock_delay.spawn(20);
+        lock_delay.land(1000.0);
+        lock_delay.refresh(1100.0, 20, false);
+        assert!(!lock_delay.is_expired(2000.0));
+        lock_delay.land(2000.0);
+        assert!(lock_delay.is_expired(2500.0));
+    }
+
+    #[test]
+    fn test_refresh_4() {
+        let mut lock_delay = LockDelay::new(500.0, 15);
+        lock_delay.spawn(20);
+        lock_delay.land(1000.0);
+        for i in 1..=15 {
+            lock_delay.refresh(1000.0 + i as f64 * 100.0, 20, true);
+        }
+        lock_delay.refresh(2600.0, 20, false);
+        assert!(!lock_delay.is_expired(3000.0));
+        lock_delay.fall(20);
+        lock_delay.land(3200.0);
+        assert!(lock_delay.is_expired(3200.0));
+    }
+
+    #[test]
+    fn test_fall() {
+        let mut lock_delay = LockDelay::new(500.0, 15);
+        lock_delay.spawn(20);
+        lock_delay.land(1000.0);
+        for i in 1..=15 {
+            lock_delay.refresh(1000.0 + i as f64 * 100.0, 20, true);
+        }
+        lock_delay.fall(19);
+        lock_delay.land(3000.0);
+        assert!(!lock_delay.is_expired(3499.0));
+        lock_delay.refresh(3400.0, 19, true);
+        assert!(!lock_delay.is_expired(3899.0));
+        assert!(lock_delay.is_expired(3900.0));
+    }
+}
@@ -0,0 +1,449 @@
+use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Error, Result};
+
+use crate::models::{
+    block::{Block, Color, MAX_BLOCKS},
+    field::FieldConfig,
+    tetromino::{
+        piece::Piece,
+        shape::{Shape, SpinRule},
+        Tetromino, TetrominoDirection, TetrominoKind,
+    },
+};
+
+// Each piece is a name, a color and `immobile` if it follows the all-spin rule, then its
+// cells facing North, eight at most: `#` for a cell, `@` for the cell it turns about and `.`
+// for nothing. Blank lines separate the pieces.
+const PENTOMINOES: &str = "
+F purple
+.##
+#@.
+.#.
+
+F' purple
+##.
+.@#
+.#.
+
+I cyan
+##@##
+
+L orange
+...#
+#@##
+
+L' blue
+#...
+#@##
+
+N green
+##..
+.#@#
+
+N' red
+..##
+#@#.
+
+P yellow
+##
+@#
+#.
+
+P' yellow
+##
+#@
+.#
+
+T purple
+###
+.@.
+.#.
+
+U orange
+#.#
+#@#
+
+V blue
+#..
+#..
+#@#
+
+W green
+#..
+#@.
+.##
+
+X red
+.#.
+#@#
+.#.
+
+Y cyan
+.#..
+#@##
+
+Y' cyan
+..#.
+#@##
+
+Z red
+##.
+.@.
+.##
+
+Z' green
+.##
+.@.
+##.
+";
+
+const EASY: &str = "
+Mono yellow
+@
+
+Domino green
+@#
+
+I3 cyan
+#@#
+
+L3 orange
+#.
+@#
+";
+
+/// How many pieces a set read from text can have, as each is told apart by a `u8`.
+pub const MAX_PIECES: usize = u8::MAX as usize + 1;
+
+/// The pieces a game deals, each with its own rotations, spawn and color.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PieceSet {
+    names: Vec<String>,
+    shapes: Vec<Rc<Shape>>,
+}
+
+impl Default for PieceSet {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl PieceSet {
+    pub fn new(names: Vec<String>, shapes: Vec<Shape>) -> Self {
+        let shapes = shapes.into_iter().map(Rc::new).collect();
+        Self { names, shapes }
+    }
+
+    pub fn standard() -> Self {
+        let names = ["I", "J", "L", "O", "S", "T", "Z"];
+        let shapes = vec![
+            Shape::i(),
+            Shape::j(),
+            Shape::l(),
+            Shape::o(),
+            Shape::s(),
+            Shape::t(),
+            Shape::z(),
+        ];
+        Self::new(names.iter().map(|name| name.to_string()).collect(), shapes)
+    }
+
+    /// The 18 one-sided pentominoes.
+    pub fn pentominoes() -> Self {
+        PENTOMINOES.parse().unwrap()
+    }
+
+    /// A monomino, a domino and the two trominoes.
+    pub fn easy() -> Self {
+        EASY.parse().unwrap()
+    }
+
+    /// Gives the all-spin rule to every piece without a spin rule of its own, except those
+    /// that look the same whichever way they face.
+    pub fn with_all_spin(self) -> Self {
+        let shapes = self
+            .shapes
+            .into_iter()
+            .map(|shape| {
+                if *shape.spin_rule() == SpinRule::None && !shape.is_rotation_invariant() {
+                    Rc::new((*shape).clone().with_spin_rule(SpinRule::Immobile))
+                } else {
+                    shape
+                }
+            })
+            .collect();
+        Self { shapes, ..self }
+    }
+
+    pub fn kinds(&self) -> Vec<TetrominoKind> {
+        self.shapes.iter().map(|shape| *shape.kind()).collect()
+    }
+
+    pub fn name(&self, kind: TetrominoKind) -> Option<&str> {
+        let idx = self.shapes.iter().position(|shape| *shape.kind() == kind)?;
+        Some(&self.names[idx])
+    }
+
+    pub fn shape(&self, kind: TetrominoKind) -> Option<&Rc<Shape>> {
+        self.shapes.iter().find(|shape| *shape.kind() == kind)
+    }
+
+    /// Builds a `kind` piece at its spawn position in a field of the given size.
+    pub fn build(&self, kind: TetrominoKind, config: &FieldConfig) -> Result<Box<dyn Tetromino>> {
+        let shape = self
+            .shape(kind)
+            .ok_or_else(|| anyhow!("no {:?} piece in the set", kind))?;
+        let (x, y) = shape.spawn();
+        let (spawn_x, spawn_y) = config.spawn();
+        let axis = Block::new(*shape.color(), spawn_x + x, spawn_y + y);
+        Ok(Box::new(Piece::new(
+            shape.clone(),
+            TetrominoDirection::North,
+            axis,
+        )))
+    }
+}
+
+impl FromStr for PieceSet {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut names = Vec::new();
+        let mut shapes = Vec::new();
+        let lines: Vec<&str> = s.lines().map(|line| line.trim()).collect();
+        for piece in lines.split(|line| line.is_empty()) {
+            let (header, rows) = match piece.split_first() {
+                Some(split) => split,
+                None => continue,
+            };
+            let mut header = header.split_whitespace();
+            let name = header.next().unwrap_or_default();
+            let color = match header.next() {
+                Some(color) => parse_color(color)?,
+                None => bail!("piece {} has no color", name),
+            };
+            let spin_rule = match header.next() {
+                None => SpinRule::None,
+                Some("immobile") => SpinRule::Immobile,
+                Some(rule) => bail!("piece {} has an unknown spin rule {}", name, rule),
+            };
+            let kind = match u8::try_from(shapes.len()) {
+                Ok(idx) => TetrominoKind::Custom(idx),
+                Err(_) => bail!("more than {} pieces", MAX_PIECES),
+            };
+            let shape = Shape::rotating(kind, color, parse_cells(name, rows)?);
+            shapes.push(shape.with_spin_rule(spin_rule));
+            names.push(name.to_string());
+        }
+        if shapes.is_empty() {
+            bail!("no pieces");
+        }
+        Ok(Self::new(names, shapes))
+    }
+}
+
+// Writes the pieces as `FromStr` reads them. That keeps their cells but not the kicks and
+// spawns of the standard pieces, which are built in code.
+impl fmt::Display for PieceSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, (name, shape)) in self.names.iter().zip(self.shapes.iter()).enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{} {}", name, color_name(*shape.color()))?;
+            if *shape.spin_rule() == SpinRule::Immobile {
+                write!(f, " immobile")?;
+            }
+            writeln!(f)?;
+            let cells = shape.cells(TetrominoDirection::North);
+            let xs = cells.iter().map(|(x, _)| *x);
+            let ys = cells.iter().map(|(_, y)| *y);
+            let (left, right) = (xs.clone().min().unwrap_or(0), xs.max().unwrap_or(0));
+            let (bottom, top) = (ys.clone().min().unwrap_or(0), ys.max().unwrap_or(0));
+            for y in (bottom..=top).rev() {
+                for x in left..=right {
+                    let cell = match (x, y) {
+                        (0, 0) => '@',
+                        _ if cells.contains(&(x, y)) => '#',
+                        _ => '.',
+                    };
+                    write!(f, "{}", cell)?;
+                }
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::Cyan => "cyan",
+        Color::Blue => "blue",
+        Color::Orange => "orange",
+        Color::Green => "green",
+        Color::Red => "red",
+        Color::Purple => "purple",
+        Color::Yellow => "yellow",
+        Color::Gray => "gray",
+    }
+}
+
+fn parse_color(color: &str) -> Result<Color> {
+    match color {
+        "cyan" => Ok(Color::Cyan),
+        "blue" => Ok(Color::Blue),
+        "orange" => Ok(Color::Orange),
+        "green" => Ok(Color::Green),
+        "red" => Ok(Color::Red),
+        "purple" => Ok(Color::Purple),
+        "yellow" => Ok(Color::Yellow),
+        "gray" => Ok(Color::Gray),
+        _ => Err(anyhow!("unknown color {}", color)),
+    }
+}
+
+fn parse_cells(name: &str, rows: &[&str]) -> Result<Vec<(i32, i32)>> {
+    let mut cells = Vec::new();
+    let mut axis = None;
+    for (row, line) in rows.iter().enumerate() {
+        for (col, cell) in line.chars().enumerate() {
+            let (x, y) = (col as i32, -(row as i32));
+            match cell {
+                '#' => cells.push((x, y)),
+                '@' if axis.is_none() => {
+                    axis = Some((x, y));
+                    cells.push((x, y));
+                }
+                '@' => bail!("piece {} has more than one axis", name),
+                '.' => {}
+                _ => bail!("piece {} has an unknown cell {:?}", name, cell),
+            }
+        }
+    }
+    if cells.len() > MAX_BLOCKS {
+        bail!("piece {} has more than {} cells", name, MAX_BLOCKS);
+    }
+    let (ax, ay) = axis.ok_or_else(|| anyhow!("piece {} has no axis", name))?;
+    Ok(cells.iter().map(|(x, y)| (x - ax, y - ay)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        let set: PieceSet = "T purple\n###\n.@.\n\nO yellow\n##\n@#\n".parse().unwrap();
+        let kinds = set.kinds();
+        assert_eq!(
+            vec![TetrominoKind::Custom(0), TetrominoKind::Custom(1)],
+            kinds
+        );
+        assert_eq!(Some("O"), set.name(kinds[1]));
+        let mut cells: Vec<(i32, i32)> = set
+            .build(kinds[0], &FieldConfig::default())
+            .unwrap()
+            .blocks()
+            .iter()
+            .map(|block| (*block.x(), *block.y()))
+            .collect();
+        cells.sort_unstable();
+        assert_eq!(vec![(3, 21), (4, 20), (4, 21), (5, 21)], cells);
+    }
+
+    #[test]
+    fn test_from_str_error() {
+        assert!("T purple\n###\n.#.".parse::<PieceSet>().is_err());
+        assert!("T\n#@#".parse::<PieceSet>().is_err());
+        assert!("T pink\n#@#".parse::<PieceSet>().is_err());
+        assert!("T purple spin\n#@#".parse::<PieceSet>().is_err());
+        assert!("T purple\n#@@".parse::<PieceSet>().is_err());
+        assert!("I purple\n####@###\n".parse::<PieceSet>().is_ok());
+        let error = "I purple\n####@####".parse::<PieceSet>().unwrap_err();
+        assert_eq!("piece I has more than 8 cells", error.to_string());
+        assert!("\n\n".parse::<PieceSet>().is_err());
+
+        let piece = |idx| format!("P{} red\n@\n", idx);
+        let pieces: Vec<String> = (0..MAX_PIECES).map(piece).collect();
+        assert!(pieces.join("\n").parse::<PieceSet>().is_ok());
+        let pieces: Vec<String> = (0..=MAX_PIECES).map(piece).collect();
+        assert!(pieces.join("\n").parse::<PieceSet>().is_err());
+    }
+
+    #[test]
+    fn test_build() {
+        let set = PieceSet::easy();
+        let config = FieldConfig::default();
+        assert!(set.build(TetrominoKind::Custom(3), &config).is_ok());
+        assert!(set.build(TetrominoKind::Custom(4), &config).is_err());
+        assert!(set.build(TetrominoKind::T, &config).is_err());
+    }
+
+    #[test]
+    fn test_pentominoes() {
+        let set = PieceSet::pentominoes();
+        assert_eq!(18, set.kinds().len());
+        assert_eq!(set, set.to_string().parse().unwrap());
+        let mut shapes = Vec::new();
+        for kind in set.kinds() {
+            let blocks = set.build(kind, &FieldConfig::default()).unwrap().blocks();
+            assert_eq!(5, blocks.len());
+            assert!(blocks.iter().all(|block| *block.y() >= 20));
+            let mut cells: Vec<(i32, i32)> = blocks
+                .iter()
+                .map(|block| (*block.x(), *block.y()))
+                .collect();
+            cells.sort_unstable();
+            assert!(!shapes.contains(&cells));
+            shapes.push(cells);
+        }
+    }
+
+    #[test]
+    fn test_with_all_spin() {
+        let set = PieceSet::standard().with_all_spin();
+        let rule = |kind| *set.shape(kind).unwrap().spin_rule();
+        assert_eq!(SpinRule::Immobile, rule(TetrominoKind::Z));
+        assert_eq!(SpinRule::ThreeCorner, rule(TetrominoKind::T));
+        assert_eq!(SpinRule::None, rule(TetrominoKind::O));
+
+        let set = PieceSet::easy().with_all_spin();
+        assert_eq!(set, set.to_string().parse().unwrap());
+    }
+
+    #[test]
+    fn test_big_pieces() {
+        let set: PieceSet = "H red\n###\n#@#\n\nO8 gray\n###\n#@.\n###".parse().unwrap();
+        let sizes: Vec<usize> = set
+            .kinds()
+            .iter()
+            .map(|kind| {
+                set.build(*kind, &FieldConfig::default())
+                    .unwrap()
+                    .blocks()
+                    .len()
+            })
+            .collect();
+        assert_eq!(vec![6, 8], sizes);
+    }
+
+    #[test]
+    fn test_easy() {
+        let set = PieceSet::easy();
+        let sizes: Vec<usize> = set
+            .kinds()
+            .iter()
+            .map(|kind| {
+                set.build(*kind, &FieldConfig::default())
+                    .unwrap()
+                    .blocks()
+                    .len()
+            })
+            .collect();
+        assert_eq!(vec![1, 2, 3, 3], sizes);
+    }
+}
@@ -0,0 +1,107 @@
+pub mod bag;
+pub mod history;
+pub mod nes;
+pub mod pure;
+pub mod tgm3;
+
+use crate::models::{rng::Rng, tetromino::TetrominoKind};
+
+/// The standard seven, in the order randomizers list them.
+pub const KINDS: [TetrominoKind; 7] = [
+    TetrominoKind::I,
+    TetrominoKind::J,
+    TetrominoKind::L,
+    TetrominoKind::O,
+    TetrominoKind::S,
+    TetrominoKind::T,
+    TetrominoKind::Z,
+];
+
+pub trait Randomizer {
+    fn next(&mut self, rng: &mut Rng) -> TetrominoKind;
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RandomizerKind {
+    SevenBag,
+    FourteenBag,
+    Pure,
+    Nes,
+    Tgm1,
+    Tgm2,
+    Tgm3,
+}
+
+impl RandomizerKind {
+    /// Builds the randomizer to deal from `kinds`, the pieces of the active set.
+    pub fn build(&self, kinds: &[TetrominoKind]) -> Box<dyn Randomizer> {
+        match self {
+            RandomizerKind::SevenBag => Box::new(bag::Bag::new(1, kinds)),
+            RandomizerKind::FourteenBag => Box::new(bag::Bag::new(2, kinds)),
+            RandomizerKind::Pure => Box::new(pure::Pure::new(kinds)),
+            RandomizerKind::Nes => Box::new(nes::Nes::new(kinds)),
+            RandomizerKind::Tgm1 => Box::new(history::History::tgm1(kinds)),
+            RandomizerKind::Tgm2 => Box::new(history::History::tgm2(kinds)),
+            RandomizerKind::Tgm3 => Box::new(tgm3::Tgm3::new(kinds)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub fn draw(randomizer: &mut dyn Randomizer, seed: u64, n: usize) -> Vec<TetrominoKind> {
+        let mut rng = Rng::new(seed);
+        (0..n).map(|_| randomizer.next(&mut rng)).collect()
+    }
+
+    // Every kind should come up within 10% of its fair share.
+    pub fn assert_uniform(kinds: &[TetrominoKind]) {
+        let fair = kinds.len() / 7;
+        for kind in KINDS.iter() {
+            let count = kinds.iter().filter(|k| *k == kind).count();
+            assert!(
+                count * 10 > fair * 9 && count * 10 < fair * 11,
+                "{:?} came up {} times",
+                kind,
+                count
+            );
+        }
+    }
+
+    // The longest run of pieces between two of the same kind.
+    pub fn max_drought(kinds: &[TetrominoKind]) -> usize {
+        KINDS
+            .iter()
+            .map(|kind| {
+                let mut last = None;
+                let mut longest = 0;
+                for (idx, k) in kinds.iter().enumerate() {
+                    if k == kind {
+                        let since = last.map_or(idx, |last: usize| idx - last - 1);
+                        longest = longest.max(since);
+                        last = Some(idx);
+                    }
+                }
+                longest
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn repeats(kinds: &[TetrominoKind]) -> usize {
+        kinds.windows(2).filter(|pair| pair[0] == pair[1]).count()
+    }
+
+    #[test]
+    fn test_build() {
+        let set = [TetrominoKind::Custom(0), TetrominoKind::Custom(1)];
+        for kind in [RandomizerKind::SevenBag, RandomizerKind::Tgm3] {
+            let kinds = draw(kind.build(&KINDS).as_mut(), 1, 70);
+            assert_eq!(70, kinds.len());
+            let kinds = draw(kind.build(&set).as_mut(), 1, 70);
+            assert!(kinds.iter().all(|kind| set.contains(kind)));
+        }
+    }
+}
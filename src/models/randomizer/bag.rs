@@ -0,0 +1,65 @@
+use crate::models::{randomizer::Randomizer, rng::Rng, tetromino::TetrominoKind};
+
+/// Deals every kind `copies` times in a random order before starting over, so a 7-bag has
+/// one of each and a 14-bag two.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Bag {
+    copies: usize,
+    kinds: Vec<TetrominoKind>,
+    bag: Vec<TetrominoKind>,
+}
+
+impl Bag {
+    pub fn new(copies: usize, kinds: &[TetrominoKind]) -> Self {
+        Self {
+            copies,
+            kinds: kinds.to_vec(),
+            bag: Vec::new(),
+        }
+    }
+}
+
+impl Randomizer for Bag {
+    fn next(&mut self, rng: &mut Rng) -> TetrominoKind {
+        if self.bag.is_empty() {
+            for _ in 0..self.copies {
+                self.bag.extend_from_slice(&self.kinds);
+            }
+        }
+        let idx = rng.below(self.bag.len());
+        self.bag.swap_remove(idx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::randomizer::{
+        tests::{assert_uniform, draw, max_drought},
+        KINDS,
+    };
+
+    #[test]
+    fn test_seven_bag() {
+        let kinds = draw(&mut Bag::new(1, &KINDS), 1, 70_000);
+        for bag in kinds.chunks(7) {
+            let mut bag = bag.to_vec();
+            bag.sort_by_key(|kind| KINDS.iter().position(|k| k == kind));
+            assert_eq!(KINDS.to_vec(), bag);
+        }
+        assert_uniform(&kinds);
+        assert!(max_drought(&kinds) <= 12);
+    }
+
+    #[test]
+    fn test_fourteen_bag() {
+        let kinds = draw(&mut Bag::new(2, &KINDS), 2, 70_000);
+        for bag in kinds.chunks(14) {
+            for kind in KINDS.iter() {
+                assert_eq!(2, bag.iter().filter(|k| *k == kind).count());
+            }
+        }
+        assert_uniform(&kinds);
+        assert!(max_drought(&kinds) <= 24);
+    }
+}
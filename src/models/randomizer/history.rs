@@ -0,0 +1,109 @@
+use std::collections::VecDeque;
+
+use crate::models::{randomizer::Randomizer, rng::Rng, tetromino::TetrominoKind};
+
+/// Remembers the last four pieces and rolls up to `rolls` times for one that isn't among
+/// them, keeping the last roll otherwise. This is how TGM and TGM2 deal.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct History {
+    kinds: Vec<TetrominoKind>,
+    rolls: usize,
+    history: VecDeque<TetrominoKind>,
+    first: bool,
+}
+
+impl History {
+    pub fn new(kinds: &[TetrominoKind], rolls: usize, history: [TetrominoKind; 4]) -> Self {
+        Self {
+            kinds: kinds.to_vec(),
+            rolls,
+            history: history.iter().copied().collect(),
+            first: true,
+        }
+    }
+
+    pub fn tgm1(kinds: &[TetrominoKind]) -> Self {
+        use TetrominoKind::Z;
+        Self::new(kinds, 4, [Z, Z, Z, Z])
+    }
+
+    pub fn tgm2(kinds: &[TetrominoKind]) -> Self {
+        use TetrominoKind::{S, Z};
+        Self::new(kinds, 6, [Z, S, S, Z])
+    }
+}
+
+impl Randomizer for History {
+    fn next(&mut self, rng: &mut Rng) -> TetrominoKind {
+        let kind = if self.first {
+            self.first = false;
+            let firsts = first_kinds(&self.kinds);
+            firsts[rng.below(firsts.len())]
+        } else {
+            let mut kind = self.kinds[rng.below(self.kinds.len())];
+            for _ in 1..self.rolls {
+                if !self.history.contains(&kind) {
+                    break;
+                }
+                kind = self.kinds[rng.below(self.kinds.len())];
+            }
+            kind
+        };
+        self.history.pop_front();
+        self.history.push_back(kind);
+        kind
+    }
+}
+
+/// The kinds a game may start with. That is never one that can only be placed with an
+/// overhang, as long as the set has anything else.
+pub fn first_kinds(kinds: &[TetrominoKind]) -> Vec<TetrominoKind> {
+    let overhangs = [TetrominoKind::O, TetrominoKind::S, TetrominoKind::Z];
+    let firsts: Vec<TetrominoKind> = kinds
+        .iter()
+        .filter(|kind| !overhangs.contains(kind))
+        .copied()
+        .collect();
+    if firsts.is_empty() {
+        kinds.to_vec()
+    } else {
+        firsts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::randomizer::{
+        tests::{assert_uniform, draw, max_drought, repeats},
+        KINDS,
+    };
+
+    #[test]
+    fn test_tgm1() {
+        let kinds = draw(&mut History::tgm1(&KINDS), 5, 70_000);
+        assert_uniform(&kinds);
+        let repeats = repeats(&kinds);
+        assert!(repeats < 2_800, "{} repeats", repeats);
+        let drought = max_drought(&kinds);
+        assert!(drought < 60, "{} pieces", drought);
+    }
+
+    #[test]
+    fn test_tgm2() {
+        let kinds = draw(&mut History::tgm2(&KINDS), 6, 70_000);
+        assert_uniform(&kinds);
+        let repeats = repeats(&kinds);
+        assert!(repeats < 1_000, "{} repeats", repeats);
+        let drought = max_drought(&kinds);
+        assert!(drought < 60, "{} pieces", drought);
+    }
+
+    #[test]
+    fn test_first() {
+        for seed in 0..100 {
+            let kind = draw(&mut History::tgm1(&KINDS), seed, 1)[0];
+            assert!(![TetrominoKind::O, TetrominoKind::S, TetrominoKind::Z].contains(&kind));
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::models::{randomizer::Randomizer, rng::Rng, tetromino::TetrominoKind};
+
+/// Rolls one of eight outcomes and rerolls once among the seven kinds when it gets the dummy
+/// eighth or the previous piece, as the NES version does.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Nes {
+    kinds: Vec<TetrominoKind>,
+    last: Option<TetrominoKind>,
+}
+
+impl Nes {
+    pub fn new(kinds: &[TetrominoKind]) -> Self {
+        Self {
+            kinds: kinds.to_vec(),
+            last: None,
+        }
+    }
+}
+
+impl Randomizer for Nes {
+    fn next(&mut self, rng: &mut Rng) -> TetrominoKind {
+        let idx = rng.below(self.kinds.len() + 1);
+        let kind = match self.kinds.get(idx) {
+            Some(kind) if Some(*kind) != self.last => *kind,
+            _ => self.kinds[rng.below(self.kinds.len())],
+        };
+        self.last = Some(kind);
+        kind
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::randomizer::{
+        tests::{assert_uniform, draw, repeats},
+        KINDS,
+    };
+
+    #[test]
+    fn test_nes() {
+        let kinds = draw(&mut Nes::new(&KINDS), 4, 70_000);
+        assert_uniform(&kinds);
+        // A repeat needs the reroll and then the same kind again, 2 / 56 of the time.
+        let repeats = repeats(&kinds);
+        assert!((2_000..3_000).contains(&repeats), "{} repeats", repeats);
+    }
+}
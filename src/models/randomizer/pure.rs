@@ -0,0 +1,40 @@
+use crate::models::{randomizer::Randomizer, rng::Rng, tetromino::TetrominoKind};
+
+/// Picks every piece independently, droughts and floods included.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pure {
+    kinds: Vec<TetrominoKind>,
+}
+
+impl Pure {
+    pub fn new(kinds: &[TetrominoKind]) -> Self {
+        Self {
+            kinds: kinds.to_vec(),
+        }
+    }
+}
+
+impl Randomizer for Pure {
+    fn next(&mut self, rng: &mut Rng) -> TetrominoKind {
+        self.kinds[rng.below(self.kinds.len())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::randomizer::{
+        tests::{assert_uniform, draw, max_drought, repeats},
+        KINDS,
+    };
+
+    #[test]
+    fn test_pure() {
+        let kinds = draw(&mut Pure::new(&KINDS), 3, 70_000);
+        assert_uniform(&kinds);
+        // About one piece in seven repeats the last one, and nothing stops long droughts.
+        let repeats = repeats(&kinds);
+        assert!((9_000..11_000).contains(&repeats), "{} repeats", repeats);
+        assert!(max_drought(&kinds) > 40);
+    }
+}
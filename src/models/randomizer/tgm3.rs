@@ -0,0 +1,107 @@
+use std::collections::VecDeque;
+
+use crate::models::{
+    randomizer::{history::first_kinds, Randomizer},
+    rng::Rng,
+    tetromino::TetrominoKind,
+};
+
+/// TGM3's randomizer: a history of four with up to six rolls, drawn from a pool of 35 that
+/// swaps each dealt piece for the one that has gone longest without showing up.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Tgm3 {
+    kinds: Vec<TetrominoKind>,
+    pool: Vec<TetrominoKind>,
+    // Kinds in the order they were last dealt, the longest drought first.
+    order: Vec<TetrominoKind>,
+    history: VecDeque<TetrominoKind>,
+    first: bool,
+}
+
+impl Tgm3 {
+    pub fn new(kinds: &[TetrominoKind]) -> Self {
+        use TetrominoKind::{S, Z};
+        Self {
+            kinds: kinds.to_vec(),
+            pool: kinds.iter().flat_map(|kind| [*kind; 5]).collect(),
+            order: kinds.to_vec(),
+            history: [S, Z, S, Z].iter().copied().collect(),
+            first: true,
+        }
+    }
+}
+
+impl Randomizer for Tgm3 {
+    fn next(&mut self, rng: &mut Rng) -> TetrominoKind {
+        let kind = if self.first {
+            self.first = false;
+            let firsts = first_kinds(&self.kinds);
+            let kind = firsts[rng.below(firsts.len())];
+            self.deal(kind);
+            kind
+        } else {
+            let mut idx = rng.below(self.pool.len());
+            for _ in 1..6 {
+                if !self.history.contains(&self.pool[idx]) {
+                    break;
+                }
+                if let Some(droughted) = self.order.first() {
+                    self.pool[idx] = *droughted;
+                }
+                idx = rng.below(self.pool.len());
+            }
+            let kind = self.pool[idx];
+            self.deal(kind);
+            self.pool[idx] = self.order[0];
+            kind
+        };
+        self.history.pop_front();
+        self.history.push_back(kind);
+        kind
+    }
+}
+
+impl Tgm3 {
+    fn deal(&mut self, kind: TetrominoKind) {
+        self.order.retain(|k| *k != kind);
+        self.order.push(kind);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::randomizer::{
+        pure::Pure,
+        tests::{assert_uniform, draw, max_drought, repeats},
+        KINDS,
+    };
+
+    #[test]
+    fn test_tgm3() {
+        let kinds = draw(&mut Tgm3::new(&KINDS), 7, 70_000);
+        assert_uniform(&kinds);
+        let repeats = repeats(&kinds);
+        assert!(repeats < 1_000, "{} repeats", repeats);
+        // The pool leans towards droughted kinds, so droughts stay far shorter than with
+        // independent picks from the same seed.
+        let drought = max_drought(&kinds);
+        assert!(drought < 25, "{} pieces", drought);
+        assert!(drought * 2 < max_drought(&draw(&mut Pure::new(&KINDS), 7, 70_000)));
+    }
+
+    #[test]
+    fn test_tgm3_start() {
+        // Every kind is in the drought order from the start, so the first pieces hand their
+        // pool slots to kinds that haven't been dealt yet.
+        for seed in 0..100 {
+            let mut tgm3 = Tgm3::new(&KINDS);
+            let mut rng = Rng::new(seed);
+            let first = tgm3.next(&mut rng);
+            assert_eq!(KINDS.len(), tgm3.order.len());
+            assert_eq!(Some(&first), tgm3.order.last());
+            let second = tgm3.next(&mut rng);
+            assert!(![first, second].contains(&tgm3.order[0]));
+        }
+    }
+}
@@ -0,0 +1,73 @@
+/// A xorshift64* generator, small and fast enough for dealing pieces and reproducible from
+/// its seed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // Run the seed through splitmix64 so that 0 and nearby seeds still start well apart.
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        Self {
+            state: if z == 0 { 1 } else { z },
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Returns a number in `0..n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+}
+
+/// Picks a seed from the clock, for games that don't ask for a particular one.
+pub fn clock_seed() -> u64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Date::now() as u64
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed() {
+        let mut rng_1 = Rng::new(42);
+        let mut rng_2 = Rng::new(42);
+        let mut rng_3 = Rng::new(43);
+        let seq_1: Vec<u64> = (0..8).map(|_| rng_1.next_u64()).collect();
+        let seq_2: Vec<u64> = (0..8).map(|_| rng_2.next_u64()).collect();
+        let seq_3: Vec<u64> = (0..8).map(|_| rng_3.next_u64()).collect();
+        assert_eq!(seq_1, seq_2);
+        assert_ne!(seq_1, seq_3);
+    }
+
+    #[test]
+    fn test_below() {
+        let mut rng = Rng::new(0);
+        let mut counts = [0; 7];
+        for _ in 0..7000 {
+            counts[rng.below(7)] += 1;
+        }
+        assert!(counts.iter().all(|count| (850..1150).contains(count)));
+    }
+}
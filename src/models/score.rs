@@ -0,0 +1,318 @@
+use crate::models::tetromino::Spin;
+
+pub trait ScoreTable {
+    fn line_clear(&self, lines: i32, spin: Spin, level: u32) -> u32;
+    fn soft_drop(&self, rows: i32) -> u32;
+    fn hard_drop(&self, rows: i32) -> u32;
+    fn combo(&self, combo: u32, level: u32) -> u32;
+    fn back_to_back(&self, points: u32) -> u32;
+    fn perfect_clear(&self, lines: i32, back_to_back: bool, level: u32) -> u32;
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Scoring {
+    Guideline,
+    Nes,
+}
+
+impl Scoring {
+    pub fn table(&self) -> Box<dyn ScoreTable> {
+        match self {
+            Scoring::Guideline => Box::new(GuidelineScoreTable),
+            Scoring::Nes => Box::new(NesScoreTable),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GuidelineScoreTable;
+
+impl ScoreTable for GuidelineScoreTable {
+    // Clears of more than four rows, which bigger pieces make, pay 200 a row like a tetris.
+    fn line_clear(&self, lines: i32, spin: Spin, level: u32) -> u32 {
+        let base = match (spin, lines) {
+            (Spin::None, 1) => 100,
+            (Spin::None, 2) => 300,
+            (Spin::None, 3) => 500,
+            (Spin::None, lines) if lines >= 4 => 200 * lines as u32,
+            (Spin::Mini, 0) => 100,
+            (Spin::Mini, 1) => 200,
+            (Spin::Mini, 2) => 400,
+            (Spin::Full, 0) => 400,
+            (Spin::Full, 1) => 800,
+            (Spin::Full, 2) => 1200,
+            (Spin::Full, 3) => 1600,
+            _ => 0,
+        };
+        base * level
+    }
+    fn soft_drop(&self, rows: i32) -> u32 {
+        rows as u32
+    }
+    fn hard_drop(&self, rows: i32) -> u32 {
+        2 * rows as u32
+    }
+    fn combo(&self, combo: u32, level: u32) -> u32 {
+        50 * combo * level
+    }
+    fn back_to_back(&self, points: u32) -> u32 {
+        points * 3 / 2
+    }
+    fn perfect_clear(&self, lines: i32, back_to_back: bool, level: u32) -> u32 {
+        let base = match lines {
+            1 => 800,
+            2 => 1200,
+            3 => 1800,
+            lines if lines >= 4 && back_to_back => 800 * lines as u32,
+            lines if lines >= 4 => 500 * lines as u32,
+            _ => 0,
+        };
+        base * level
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct NesScoreTable;
+
+impl ScoreTable for NesScoreTable {
+    // Levels count from 1 here, so level 1 pays what NES level 0 does. Spins pay nothing extra,
+    // and clears of more than four rows pay 300 a row like a tetris.
+    fn line_clear(&self, lines: i32, _spin: Spin, level: u32) -> u32 {
+        let base = match lines {
+            1 => 40,
+            2 => 100,
+            3 => 300,
+            lines if lines >= 4 => 300 * lines as u32,
+            _ => 0,
+        };
+        base * level
+    }
+    fn soft_drop(&self, rows: i32) -> u32 {
+        rows as u32
+    }
+    fn hard_drop(&self, _rows: i32) -> u32 {
+        0
+    }
+    fn combo(&self, _combo: u32, _level: u32) -> u32 {
+        0
+    }
+    fn back_to_back(&self, points: u32) -> u32 {
+        points
+    }
+    fn perfect_clear(&self, _lines: i32, _back_to_back: bool, _level: u32) -> u32 {
+        0
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Score {
+    points: u32,
+    start_level: u32,
+    level: u32,
+    lines: u32,
+    combo: Option<u32>,
+    back_to_back: bool,
+}
+
+impl Score {
+    pub fn new(level: u32) -> Self {
+        Self {
+            points: 0,
+            start_level: level,
+            level,
+            lines: 0,
+            combo: None,
+            back_to_back: false,
+        }
+    }
+
+    pub fn points(&self) -> &u32 {
+        &self.points
+    }
+
+    pub fn level(&self) -> &u32 {
+        &self.level
+    }
+
+    pub fn lines(&self) -> &u32 {
+        &self.lines
+    }
+
+    /// Counts the clears in a row after the first one, or `None` once a piece clears nothing.
+    pub fn combo(&self) -> &Option<u32> {
+        &self.combo
+    }
+
+    /// Whether the last clear was of four or more rows or a spin, so that the next one of those
+    /// is a B2B.
+    pub fn back_to_back(&self) -> &bool {
+        &self.back_to_back
+    }
+
+    pub fn soft_drop(&mut self, table: &dyn ScoreTable, rows: i32) -> u32 {
+        let points = table.soft_drop(rows);
+        self.points += points;
+        points
+    }
+
+    pub fn hard_drop(&mut self, table: &dyn ScoreTable, rows: i32) -> u32 {
+        let points = table.hard_drop(rows);
+        self.points += points;
+        points
+    }
+
+    /// Scores a locked piece that cleared `lines` rows, keeping track of the combo and of
+    /// back-to-back tetrises and spins. The level goes up every 10 lines.
+    pub fn clear(&mut self, table: &dyn ScoreTable, lines: i32, spin: Spin) -> u32 {
+        if lines == 0 {
+            self.combo = None;
+            let points = table.line_clear(lines, spin, self.level);
+            self.points += points;
+            return points;
+        }
+
+        let mut points = table.line_clear(lines, spin, self.level);
+        let difficult = lines >= 4 || spin != Spin::None;
+        if difficult && self.back_to_back {
+            points = table.back_to_back(points);
+        }
+        self.back_to_back = difficult;
+
+        let combo = self.combo.map_or(0, |combo| combo + 1);
+        self.combo = Some(combo);
+        points += table.combo(combo, self.level);
+
+        self.lines += lines as u32;
+        self.level = self.start_level + self.lines / 10;
+        self.points += points;
+        points
+    }
+
+    /// Adds the bonus for a clear of `lines` rows that left the field empty. It pays the
+    /// level the clear was made at, and `back_to_back` tells whether it continued a B2B.
+    pub fn perfect_clear(
+        &mut self,
+        table: &dyn ScoreTable,
+        lines: i32,
+        back_to_back: bool,
+        level: u32,
+    ) -> u32 {
+        let points = table.perfect_clear(lines, back_to_back, level);
+        self.points += points;
+        points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clear_1() {
+        let mut score = Score::new(2);
+        let table = GuidelineScoreTable;
+        assert_eq!(200, score.clear(&table, 1, Spin::None));
+        assert_eq!(600 + 100, score.clear(&table, 2, Spin::None));
+        assert_eq!(0, score.clear(&table, 0, Spin::None));
+        assert_eq!(1000, score.clear(&table, 3, Spin::None));
+        assert_eq!(1900, *score.points());
+        assert_eq!(6, *score.lines());
+    }
+
+    #[test]
+    fn test_clear_2() {
+        let mut score = Score::new(1);
+        let table = GuidelineScoreTable;
+        assert_eq!(800, score.clear(&table, 4, Spin::None));
+        assert_eq!(0, score.clear(&table, 0, Spin::None));
+        assert_eq!(1200, score.clear(&table, 4, Spin::None));
+        assert_eq!(0, score.clear(&table, 0, Spin::None));
+        assert_eq!(100, score.clear(&table, 1, Spin::None));
+        assert_eq!(0, score.clear(&table, 0, Spin::None));
+        assert_eq!(800, score.clear(&table, 4, Spin::None));
+    }
+
+    #[test]
+    fn test_clear_3() {
+        let mut score = Score::new(1);
+        let table = NesScoreTable;
+        assert_eq!(1200, score.clear(&table, 4, Spin::None));
+        assert_eq!(1200, score.clear(&table, 4, Spin::None));
+        assert_eq!(40, score.clear(&table, 1, Spin::None));
+    }
+
+    #[test]
+    fn test_clear_4() {
+        let mut score = Score::new(1);
+        let table = GuidelineScoreTable;
+        assert_eq!(1000, score.clear(&table, 5, Spin::None));
+        assert!(*score.back_to_back());
+        assert_eq!(1200 + 50, score.clear(&table, 4, Spin::None));
+        assert_eq!(1500 + 100, score.clear(&table, 5, Spin::None));
+        assert_eq!(1500, Score::new(1).clear(&NesScoreTable, 5, Spin::None));
+        assert_eq!(2500, score.perfect_clear(&table, 5, false, 1));
+        assert_eq!(4000, score.perfect_clear(&table, 5, true, 1));
+    }
+
+    #[test]
+    fn test_combo() {
+        let mut score = Score::new(1);
+        let table = GuidelineScoreTable;
+        assert_eq!(None, *score.combo());
+        score.clear(&table, 1, Spin::None);
+        score.clear(&table, 4, Spin::None);
+        assert_eq!(Some(1), *score.combo());
+        assert!(*score.back_to_back());
+        score.clear(&table, 0, Spin::Full);
+        assert_eq!(None, *score.combo());
+        assert!(*score.back_to_back());
+        score.clear(&table, 2, Spin::None);
+        assert_eq!(Some(0), *score.combo());
+        assert!(!*score.back_to_back());
+    }
+
+    #[test]
+    fn test_clear_spin() {
+        let mut score = Score::new(1);
+        let table = GuidelineScoreTable;
+        assert_eq!(400, score.clear(&table, 0, Spin::Full));
+        assert_eq!(1200, score.clear(&table, 2, Spin::Full));
+        assert_eq!(600 + 50, score.clear(&table, 2, Spin::Mini));
+        assert_eq!(1200 + 100, score.clear(&table, 4, Spin::None));
+        assert_eq!(100, score.clear(&table, 0, Spin::Mini));
+        assert_eq!(100, score.clear(&table, 1, Spin::None));
+        assert_eq!(0, score.clear(&NesScoreTable, 0, Spin::Full));
+    }
+
+    #[test]
+    fn test_perfect_clear() {
+        let mut score = Score::new(2);
+        let table = GuidelineScoreTable;
+        assert_eq!(1600, score.perfect_clear(&table, 1, false, 2));
+        assert_eq!(4000, score.perfect_clear(&table, 4, false, 2));
+        assert_eq!(6400, score.perfect_clear(&table, 4, true, 2));
+        assert_eq!(0, score.perfect_clear(&NesScoreTable, 4, true, 2));
+        assert_eq!(12000, *score.points());
+    }
+
+    #[test]
+    fn test_level() {
+        let mut score = Score::new(3);
+        for _ in 0..9 {
+            score.clear(&GuidelineScoreTable, 1, Spin::None);
+        }
+        assert_eq!(3, *score.level());
+        score.clear(&GuidelineScoreTable, 2, Spin::None);
+        assert_eq!(4, *score.level());
+        assert_eq!(11, *score.lines());
+    }
+
+    #[test]
+    fn test_drop() {
+        let mut score = Score::new(1);
+        assert_eq!(5, score.soft_drop(&GuidelineScoreTable, 5));
+        assert_eq!(36, score.hard_drop(&GuidelineScoreTable, 18));
+        assert_eq!(0, score.hard_drop(&NesScoreTable, 18));
+        assert_eq!(41, *score.points());
+    }
+}
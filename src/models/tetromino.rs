@@ -1,19 +1,91 @@
-pub mod i;
-pub mod j;
-pub mod l;
-pub mod o;
-pub mod s;
-pub mod t;
-pub mod z;
+pub mod piece;
+pub mod shape;
+pub mod srs;
 
-use crate::models::block::{Block, Direction};
+use crate::models::{
+    block::{Blocks, Direction},
+    field::Field,
+    tetromino::srs::Kick,
+};
 
 pub trait Tetromino {
     fn move_(&mut self, move_dir: MoveDirection);
-    fn rotate(&mut self, rotate_dir: RotateDirection);
-    fn dry_move(&self, move_dir: MoveDirection) -> Vec<Block>;
-    fn dry_rotate(&self, rotate_dir: RotateDirection) -> Vec<Block>;
-    fn blocks(&self) -> Vec<Block>;
+    fn rotate(&mut self, rotate_dir: RotateDirection, kick: &Kick);
+    fn dry_move(&self, move_dir: MoveDirection) -> Blocks;
+    fn dry_rotate(&self, rotate_dir: RotateDirection, kick: &Kick) -> Blocks;
+    fn kicks(&self, rotate_dir: RotateDirection) -> &[Kick];
+    fn blocks(&self) -> Blocks;
+    fn kind(&self) -> TetrominoKind;
+
+    /// Tells whether the piece, about to lock in `field`, counts as a spin.
+    fn spin(&self, _field: &Field) -> Spin {
+        Spin::None
+    }
+
+    /// Tells whether the piece can move neither sideways nor up, as after a spin into a slot.
+    fn is_immobile(&self, field: &Field) -> bool {
+        [MoveDirection::Left, MoveDirection::Right, MoveDirection::Up]
+            .iter()
+            .all(|move_dir| !field.is_vacant(&self.dry_move(*move_dir)))
+    }
+
+    /// Tries each kick in order and applies the first one the field has room for.
+    fn rotate_in(&mut self, rotate_dir: RotateDirection, field: &Field) -> bool {
+        let kick = self
+            .kicks(rotate_dir)
+            .iter()
+            .find(|kick| field.is_vacant(&self.dry_rotate(rotate_dir, kick)))
+            .copied();
+        match kick {
+            Some(kick) => {
+                self.rotate(rotate_dir, &kick);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves down until the field blocks the piece and returns how many rows it fell.
+    fn drop_in(&mut self, field: &Field) -> i32 {
+        let mut rows = 0;
+        while field.is_vacant(&self.dry_move(MoveDirection::Down)) {
+            self.move_(MoveDirection::Down);
+            rows += 1;
+        }
+        rows
+    }
+
+    /// Returns the blocks where the piece would land if it dropped straight down.
+    fn dry_drop(&self, field: &Field) -> Blocks {
+        let mut blocks = self.blocks();
+        loop {
+            let moved = blocks.map(|block| block.move_(Direction::Down));
+            if !field.is_vacant(&moved) {
+                return blocks;
+            }
+            blocks = moved;
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TetrominoKind {
+    I,
+    J,
+    L,
+    O,
+    S,
+    T,
+    Z,
+    // A piece from a set other than the standard seven, by its index in that set.
+    Custom(u8),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Spin {
+    None,
+    Mini,
+    Full,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -24,7 +96,7 @@ pub enum TetrominoDirection {
     West,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum RotateDirection {
     Left,
     Right,
@@ -49,10 +121,11 @@ impl TetrominoDirection {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum MoveDirection {
     Left,
     Right,
+    Up,
     Down,
 }
 
@@ -61,7 +134,61 @@ impl From<MoveDirection> for Direction {
         match move_dir {
             MoveDirection::Left => Direction::Left,
             MoveDirection::Right => Direction::Right,
+            MoveDirection::Up => Direction::Up,
             MoveDirection::Down => Direction::Down,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    use crate::models::{
+        block::{Block, Color},
+        field::FieldConfig,
+        tetromino::{piece::Piece, shape::Shape},
+    };
+
+    #[test]
+    fn test_drop_in_1() {
+        let field = Field::new(FieldConfig::default());
+        let mut t = build_t(TetrominoDirection::North, Block::new(Color::Purple, 4, 20));
+        assert_eq!(20, t.drop_in(&field));
+        assert_eq!(
+            build_t(TetrominoDirection::North, Block::new(Color::Purple, 4, 0)),
+            t
+        );
+    }
+
+    #[test]
+    fn test_drop_in_2() {
+        let mut field = Field::new(FieldConfig::default());
+        field.fix_blocks(vec![Block::new(Color::Cyan, 5, 7)]);
+        let mut t = build_t(TetrominoDirection::North, Block::new(Color::Purple, 4, 20));
+        assert_eq!(12, t.drop_in(&field));
+        assert_eq!(0, t.drop_in(&field));
+        assert_eq!(
+            build_t(TetrominoDirection::North, Block::new(Color::Purple, 4, 8)),
+            t
+        );
+    }
+
+    #[test]
+    fn test_dry_drop() {
+        let mut field = Field::new(FieldConfig::default());
+        field.fix_blocks(vec![Block::new(Color::Cyan, 3, 4)]);
+        let t = build_t(TetrominoDirection::North, Block::new(Color::Purple, 4, 20));
+        let expected = build_t(TetrominoDirection::North, Block::new(Color::Purple, 4, 5)).blocks();
+        assert_eq!(expected, t.dry_drop(&field));
+        assert_eq!(
+            build_t(TetrominoDirection::North, Block::new(Color::Purple, 4, 20)),
+            t
+        );
+    }
+
+    fn build_t(dir: TetrominoDirection, axis: Block) -> Piece {
+        Piece::new(Rc::new(Shape::t()), dir, axis)
+    }
+}
@@ -0,0 +1,198 @@
+use std::rc::Rc;
+
+use crate::models::{
+    block::{Block, Blocks, Direction},
+    field::Field,
+    tetromino::{
+        shape::{Shape, SpinRule},
+        srs::Kick,
+        MoveDirection, RotateDirection, Spin, Tetromino, TetrominoDirection, TetrominoKind,
+    },
+};
+
+/// A piece in play, placed by its axis and direction and shaped by a shared `Shape`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Piece {
+    shape: Rc<Shape>,
+    dir: TetrominoDirection,
+    axis: Block,
+    // The kick of the last rotation, as long as the piece hasn't moved since.
+    last_kick: Option<Kick>,
+}
+
+impl Tetromino for Piece {
+    fn move_(&mut self, move_dir: MoveDirection) {
+        let dir = Direction::from(move_dir);
+        self.axis = self.axis.move_(dir);
+        self.last_kick = None;
+    }
+    fn rotate(&mut self, rotate_dir: RotateDirection, kick: &Kick) {
+        self.axis = self.axis.kick(kick);
+        self.dir = self.dir.rotate(rotate_dir);
+        self.last_kick = Some(*kick);
+    }
+    fn dry_move(&self, move_dir: MoveDirection) -> Blocks {
+        let dir = Direction::from(move_dir);
+        self.blocks_at(self.dir, self.axis.move_(dir))
+    }
+    fn dry_rotate(&self, rotate_dir: RotateDirection, kick: &Kick) -> Blocks {
+        self.blocks_at(self.dir.rotate(rotate_dir), self.axis.kick(kick))
+    }
+    fn kicks(&self, rotate_dir: RotateDirection) -> &[Kick] {
+        self.shape.kicks().get(self.dir, rotate_dir)
+    }
+    fn blocks(&self) -> Blocks {
+        self.blocks_at(self.dir, self.axis)
+    }
+    fn kind(&self) -> TetrominoKind {
+        *self.shape.kind()
+    }
+    fn spin(&self, field: &Field) -> Spin {
+        match (self.shape.spin_rule(), self.last_kick) {
+            (SpinRule::ThreeCorner, Some(kick)) => self.three_corner_spin(field, kick),
+            (SpinRule::Immobile, Some(_)) if self.is_immobile(field) => Spin::Mini,
+            _ => Spin::None,
+        }
+    }
+}
+
+impl Piece {
+    pub fn new(shape: Rc<Shape>, dir: TetrominoDirection, axis: Block) -> Self {
+        Self {
+            shape,
+            dir,
+            axis,
+            last_kick: None,
+        }
+    }
+
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    pub fn dir(&self) -> &TetrominoDirection {
+        &self.dir
+    }
+
+    pub fn axis(&self) -> &Block {
+        &self.axis
+    }
+}
+
+impl Piece {
+    fn blocks_at(&self, dir: TetrominoDirection, axis: Block) -> Blocks {
+        self.shape
+            .cells(dir)
+            .iter()
+            .map(|(x, y)| axis.offset(*x, *y))
+            .collect()
+    }
+
+    // The 3-corner rule: a rotated T with three of the four cells diagonal to its centre
+    // occupied is a T-spin, and a mini unless both corners it points at are occupied or
+    // it got there with the 1x2 kick.
+    fn three_corner_spin(&self, field: &Field, kick: Kick) -> Spin {
+        let is_occupied = |x: i32, y: i32| !field.is_vacant(&[self.axis.offset(x, y)]);
+        let corners = [(-1, 1), (1, 1), (1, -1), (-1, -1)];
+        if corners.iter().filter(|(x, y)| is_occupied(*x, *y)).count() < 3 {
+            return Spin::None;
+        }
+        let front = match self.dir {
+            TetrominoDirection::North => [(-1, 1), (1, 1)],
+            TetrominoDirection::East => [(1, 1), (1, -1)],
+            TetrominoDirection::South => [(1, -1), (-1, -1)],
+            TetrominoDirection::West => [(-1, -1), (-1, 1)],
+        };
+        if front.iter().all(|(x, y)| is_occupied(*x, *y))
+            || (kick.x().abs() == 1 && kick.y().abs() == 2)
+        {
+            Spin::Full
+        } else {
+            Spin::Mini
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::block::Color;
+
+    #[test]
+    fn test_spin_1() {
+        let field: Field = "
+            ___G______
+            GGG___GGGG
+            GGGG_GGGGG
+        "
+        .parse()
+        .unwrap();
+        let mut t = build_t(TetrominoDirection::East, Block::new(Color::Purple, 4, 1));
+        assert_eq!(Spin::None, t.spin(&field));
+        t.rotate(RotateDirection::Right, &Kick::new(0, 0));
+        assert_eq!(Spin::Full, t.spin(&field));
+    }
+
+    #[test]
+    fn test_spin_2() {
+        let field: Field = "
+            G_________
+            ____GGGGGG
+        "
+        .parse()
+        .unwrap();
+        let mut t = build_t(TetrominoDirection::East, Block::new(Color::Purple, 1, 0));
+        t.rotate(RotateDirection::Left, &Kick::new(0, 0));
+        assert_eq!(Spin::Mini, t.spin(&field));
+
+        let mut t = build_t(TetrominoDirection::East, Block::new(Color::Purple, 0, 2));
+        t.rotate(RotateDirection::Left, &Kick::new(1, -2));
+        assert_eq!(Spin::Full, t.spin(&field));
+    }
+
+    #[test]
+    fn test_spin_3() {
+        let field: Field = "
+            ___G______
+            GGG___GGGG
+            GGGG_GGGGG
+        "
+        .parse()
+        .unwrap();
+        let mut t = build_t(TetrominoDirection::East, Block::new(Color::Purple, 4, 2));
+        t.rotate(RotateDirection::Right, &Kick::new(0, 0));
+        t.move_(MoveDirection::Down);
+        assert_eq!(Spin::None, t.spin(&field));
+    }
+
+    #[test]
+    fn test_spin_4() {
+        let field: Field = "
+            __G_______
+            ___G______
+            G__G______
+            G_G_______
+        "
+        .parse()
+        .unwrap();
+        let shape = Shape::z().with_spin_rule(SpinRule::Immobile);
+        let mut z = Piece::new(
+            Rc::new(shape),
+            TetrominoDirection::North,
+            Block::new(Color::Red, 1, 1),
+        );
+        z.rotate(RotateDirection::Right, &Kick::new(0, 0));
+        assert_eq!(Spin::Mini, z.spin(&field));
+        let mut z = Piece::new(
+            Rc::new(Shape::z()),
+            TetrominoDirection::North,
+            Block::new(Color::Red, 1, 1),
+        );
+        z.rotate(RotateDirection::Right, &Kick::new(0, 0));
+        assert_eq!(Spin::None, z.spin(&field));
+    }
+
+    fn build_t(dir: TetrominoDirection, axis: Block) -> Piece {
+        Piece::new(Rc::new(Shape::t()), dir, axis)
+    }
+}
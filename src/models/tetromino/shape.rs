@@ -0,0 +1,282 @@
+use crate::models::{
+    block::{Color, MAX_BLOCKS},
+    tetromino::{
+        srs::{self, Kick},
+        RotateDirection, TetrominoDirection, TetrominoKind,
+    },
+};
+
+/// Describes a piece as data: the cells it covers around its axis in each direction, the
+/// kicks it tries when rotating and how it is checked for spins.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Shape {
+    kind: TetrominoKind,
+    color: Color,
+    // Offsets from the axis, facing North, East, South and West in that order.
+    cells: [Vec<(i32, i32)>; 4],
+    kicks: KickTable,
+    spin_rule: SpinRule,
+    // Where the axis spawns, relative to the middle of the row above the visible field.
+    spawn: (i32, i32),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KickTable(Vec<Vec<Kick>>);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SpinRule {
+    None,
+    ThreeCorner,
+    /// Any rotation into a spot the piece can't slide out of is a mini, as under all-spin.
+    Immobile,
+}
+
+impl Shape {
+    pub fn new(
+        kind: TetrominoKind,
+        color: Color,
+        cells: [Vec<(i32, i32)>; 4],
+        kicks: KickTable,
+        spin_rule: SpinRule,
+    ) -> Self {
+        assert!(
+            cells.iter().all(|cells| cells.len() <= MAX_BLOCKS),
+            "a piece covers at most {} cells",
+            MAX_BLOCKS
+        );
+        Self {
+            kind,
+            color,
+            cells,
+            kicks,
+            spin_rule,
+            spawn: (0, 0),
+        }
+    }
+
+    /// Builds a piece from its cells facing North, turning them about the axis for the other
+    /// directions. It kicks like J, L, S, T and Z, and spawns with its lowest cells on the
+    /// row above the visible field.
+    pub fn rotating(kind: TetrominoKind, color: Color, cells: Vec<(i32, i32)>) -> Self {
+        let east = cells.iter().map(|(x, y)| (*y, -x)).collect();
+        let south = cells.iter().map(|(x, y)| (-x, -y)).collect();
+        let west = cells.iter().map(|(x, y)| (-y, *x)).collect();
+        let bottom = cells.iter().map(|(_, y)| *y).min().unwrap_or(0);
+        let cells = [cells, east, south, west];
+        let kicks = KickTable::from_fn(srs::jlstz_kicks);
+        Self::new(kind, color, cells, kicks, SpinRule::None).with_spawn(0, -bottom)
+    }
+
+    pub fn with_spawn(self, x: i32, y: i32) -> Self {
+        Self {
+            spawn: (x, y),
+            ..self
+        }
+    }
+
+    pub fn with_spin_rule(self, spin_rule: SpinRule) -> Self {
+        Self { spin_rule, ..self }
+    }
+
+    // The I turns about its second block rather than the centre of its box; its kicks make
+    // up for that.
+    pub fn i() -> Self {
+        let cells = [
+            vec![(-1, 0), (0, 0), (1, 0), (2, 0)],
+            vec![(0, 1), (0, 0), (0, -1), (0, -2)],
+            vec![(-2, 0), (-1, 0), (0, 0), (1, 0)],
+            vec![(0, 2), (0, 1), (0, 0), (0, -1)],
+        ];
+        let kicks = KickTable::from_fn(srs::i_kicks);
+        Self::new(TetrominoKind::I, Color::Cyan, cells, kicks, SpinRule::None)
+    }
+
+    pub fn j() -> Self {
+        let cells = [
+            vec![(-1, 1), (-1, 0), (0, 0), (1, 0)],
+            vec![(1, 1), (0, 1), (0, 0), (0, -1)],
+            vec![(-1, 0), (0, 0), (1, 0), (1, -1)],
+            vec![(0, 1), (0, 0), (0, -1), (-1, -1)],
+        ];
+        let kicks = KickTable::from_fn(srs::jlstz_kicks);
+        Self::new(TetrominoKind::J, Color::Blue, cells, kicks, SpinRule::None)
+    }
+
+    pub fn l() -> Self {
+        let cells = [
+            vec![(-1, 0), (0, 0), (1, 0), (1, 1)],
+            vec![(0, 1), (0, 0), (0, -1), (1, -1)],
+            vec![(-1, -1), (-1, 0), (0, 0), (1, 0)],
+            vec![(-1, 1), (0, 1), (0, 0), (0, -1)],
+        ];
+        let kicks = KickTable::from_fn(srs::jlstz_kicks);
+        Self::new(
+            TetrominoKind::L,
+            Color::Orange,
+            cells,
+            kicks,
+            SpinRule::None,
+        )
+    }
+
+    pub fn o() -> Self {
+        let cells = vec![(0, 0), (0, 1), (1, 0), (1, 1)];
+        let cells = [cells.clone(), cells.clone(), cells.clone(), cells];
+        let kicks = KickTable::from_fn(|_, _| srs::o_kicks());
+        Self::new(
+            TetrominoKind::O,
+            Color::Yellow,
+            cells,
+            kicks,
+            SpinRule::None,
+        )
+    }
+
+    pub fn s() -> Self {
+        let cells = [
+            vec![(-1, 0), (0, 0), (0, 1), (1, 1)],
+            vec![(0, 1), (0, 0), (1, 0), (1, -1)],
+            vec![(-1, -1), (0, -1), (0, 0), (1, 0)],
+            vec![(-1, 1), (-1, 0), (0, 0), (0, -1)],
+        ];
+        let kicks = KickTable::from_fn(srs::jlstz_kicks);
+        Self::new(TetrominoKind::S, Color::Green, cells, kicks, SpinRule::None)
+    }
+
+    pub fn t() -> Self {
+        let cells = [
+            vec![(-1, 0), (0, 0), (0, 1), (1, 0)],
+            vec![(0, 1), (0, 0), (1, 0), (0, -1)],
+            vec![(-1, 0), (0, 0), (0, -1), (1, 0)],
+            vec![(0, 1), (0, 0), (-1, 0), (0, -1)],
+        ];
+        let kicks = KickTable::from_fn(srs::jlstz_kicks);
+        let spin_rule = SpinRule::ThreeCorner;
+        Self::new(TetrominoKind::T, Color::Purple, cells, kicks, spin_rule)
+    }
+
+    pub fn z() -> Self {
+        let cells = [
+            vec![(-1, 1), (0, 1), (0, 0), (1, 0)],
+            vec![(1, 1), (1, 0), (0, 0), (0, -1)],
+            vec![(-1, 0), (0, 0), (0, -1), (1, -1)],
+            vec![(-1, -1), (-1, 0), (0, 0), (0, 1)],
+        ];
+        let kicks = KickTable::from_fn(srs::jlstz_kicks);
+        Self::new(TetrominoKind::Z, Color::Red, cells, kicks, SpinRule::None)
+    }
+
+    pub fn kind(&self) -> &TetrominoKind {
+        &self.kind
+    }
+
+    pub fn color(&self) -> &Color {
+        &self.color
+    }
+
+    pub fn cells(&self, dir: TetrominoDirection) -> &[(i32, i32)] {
+        &self.cells[dir_idx(dir)]
+    }
+
+    pub fn kicks(&self) -> &KickTable {
+        &self.kicks
+    }
+
+    pub fn spin_rule(&self) -> &SpinRule {
+        &self.spin_rule
+    }
+
+    pub fn spawn(&self) -> &(i32, i32) {
+        &self.spawn
+    }
+
+    /// Tells whether the piece covers the same cells in every direction, as the O does.
+    pub fn is_rotation_invariant(&self) -> bool {
+        let north = normalize(self.cells(TetrominoDirection::North));
+        DIRS.iter().all(|dir| normalize(self.cells(*dir)) == north)
+    }
+}
+
+impl KickTable {
+    pub fn from_fn(kicks: fn(TetrominoDirection, RotateDirection) -> Vec<Kick>) -> Self {
+        let mut table = Vec::new();
+        for dir in DIRS.iter() {
+            table.push(kicks(*dir, RotateDirection::Left));
+            table.push(kicks(*dir, RotateDirection::Right));
+        }
+        Self(table)
+    }
+
+    pub fn get(&self, dir: TetrominoDirection, rotate_dir: RotateDirection) -> &[Kick] {
+        let rotate_idx = match rotate_dir {
+            RotateDirection::Left => 0,
+            RotateDirection::Right => 1,
+        };
+        &self.0[2 * dir_idx(dir) + rotate_idx]
+    }
+}
+
+const DIRS: [TetrominoDirection; 4] = [
+    TetrominoDirection::North,
+    TetrominoDirection::East,
+    TetrominoDirection::South,
+    TetrominoDirection::West,
+];
+
+// Moves the cells to touch both axes and sorts them, so that shapes compare regardless of
+// where the axis sits.
+fn normalize(cells: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    let left = cells.iter().map(|(x, _)| *x).min().unwrap_or(0);
+    let bottom = cells.iter().map(|(_, y)| *y).min().unwrap_or(0);
+    let mut cells: Vec<(i32, i32)> = cells.iter().map(|(x, y)| (x - left, y - bottom)).collect();
+    cells.sort_unstable();
+    cells
+}
+
+fn dir_idx(dir: TetrominoDirection) -> usize {
+    match dir {
+        TetrominoDirection::North => 0,
+        TetrominoDirection::East => 1,
+        TetrominoDirection::South => 2,
+        TetrominoDirection::West => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotating() {
+        let shape = Shape::rotating(
+            TetrominoKind::T,
+            Color::Purple,
+            vec![(-1, 0), (0, 0), (0, 1), (1, 0)],
+        );
+        let standard = Shape::t();
+        for dir in DIRS.iter() {
+            let mut cells = shape.cells(*dir).to_vec();
+            let mut expected = standard.cells(*dir).to_vec();
+            cells.sort_unstable();
+            expected.sort_unstable();
+            assert_eq!(expected, cells);
+        }
+        assert_eq!((0, 0), *shape.spawn());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_new() {
+        let cells = (0..MAX_BLOCKS as i32 + 1).map(|x| (x, 0)).collect();
+        Shape::rotating(TetrominoKind::Custom(0), Color::Red, cells);
+    }
+
+    #[test]
+    fn test_is_rotation_invariant() {
+        assert!(Shape::o().is_rotation_invariant());
+        assert!(!Shape::i().is_rotation_invariant());
+        assert!(!Shape::t().is_rotation_invariant());
+        let square = vec![(0, 0), (1, 0), (0, 1), (1, 1)];
+        assert!(Shape::rotating(TetrominoKind::O, Color::Yellow, square).is_rotation_invariant());
+    }
+}
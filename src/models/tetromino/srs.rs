@@ -0,0 +1,256 @@
+use derive_new::new;
+
+use crate::models::tetromino::{RotateDirection, TetrominoDirection};
+
+#[derive(Clone, Copy, Debug, Eq, new, PartialEq)]
+pub struct Kick {
+    x: i32,
+    y: i32,
+}
+
+impl Kick {
+    pub fn x(&self) -> &i32 {
+        &self.x
+    }
+
+    pub fn y(&self) -> &i32 {
+        &self.y
+    }
+}
+
+pub fn jlstz_kicks(dir: TetrominoDirection, rotate_dir: RotateDirection) -> Vec<Kick> {
+    use RotateDirection::{Left, Right};
+    use TetrominoDirection::{East, North, South, West};
+
+    let table = match (dir, rotate_dir) {
+        (North, Right) => [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+        (East, Left) => [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+        (East, Right) => [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+        (South, Left) => [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+        (South, Right) => [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+        (West, Left) => [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+        (West, Right) => [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+        (North, Left) => [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+    };
+    to_kicks(&table)
+}
+
+// The I tetromino turns about its second block instead of the centre of its 4x4 box,
+// so each entry below is the standard SRS kick plus the shift that recentres the box.
+pub fn i_kicks(dir: TetrominoDirection, rotate_dir: RotateDirection) -> Vec<Kick> {
+    use RotateDirection::{Left, Right};
+    use TetrominoDirection::{East, North, South, West};
+
+    let table = match (dir, rotate_dir) {
+        (North, Right) => [(1, 0), (-1, 0), (2, 0), (-1, -1), (2, 2)],
+        (East, Left) => [(-1, 0), (1, 0), (-2, 0), (1, 1), (-2, -2)],
+        (East, Right) => [(0, -1), (-1, -1), (2, -1), (-1, 1), (2, -2)],
+        (South, Left) => [(0, 1), (1, 1), (-2, 1), (1, -1), (-2, 2)],
+        (South, Right) => [(-1, 0), (1, 0), (-2, 0), (1, 1), (-2, -2)],
+        (West, Left) => [(1, 0), (-1, 0), (2, 0), (-1, -1), (2, 2)],
+        (West, Right) => [(0, 1), (1, 1), (-2, 1), (1, -1), (-2, 2)],
+        (North, Left) => [(0, -1), (-1, -1), (2, -1), (-1, 1), (2, -2)],
+    };
+    to_kicks(&table)
+}
+
+pub fn o_kicks() -> Vec<Kick> {
+    vec![Kick::new(0, 0)]
+}
+
+fn to_kicks(table: &[(i32, i32)]) -> Vec<Kick> {
+    table.iter().map(|(x, y)| Kick::new(*x, *y)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::models::{
+        block::{Block, Color},
+        field::{Field, FieldConfig},
+        tetromino::{piece::Piece, shape::Shape, Tetromino},
+    };
+
+    type Build = fn(TetrominoDirection, Block) -> Box<dyn Tetromino>;
+
+    const DIRS: [TetrominoDirection; 4] = [
+        TetrominoDirection::North,
+        TetrominoDirection::East,
+        TetrominoDirection::South,
+        TetrominoDirection::West,
+    ];
+
+    #[test]
+    fn test_jlstz_kicks() {
+        let builds: [Build; 5] = [
+            |dir, axis| Box::new(Piece::new(Rc::new(Shape::j()), dir, axis)),
+            |dir, axis| Box::new(Piece::new(Rc::new(Shape::l()), dir, axis)),
+            |dir, axis| Box::new(Piece::new(Rc::new(Shape::s()), dir, axis)),
+            |dir, axis| Box::new(Piece::new(Rc::new(Shape::t()), dir, axis)),
+            |dir, axis| Box::new(Piece::new(Rc::new(Shape::z()), dir, axis)),
+        ];
+        for build in builds.iter() {
+            for dir in DIRS.iter() {
+                for rotate_dir in [RotateDirection::Left, RotateDirection::Right] {
+                    let table = expected_jlstz_kicks(*dir, rotate_dir);
+                    for (idx, kick) in table.iter().enumerate() {
+                        assert_kick(*build, *dir, rotate_dir, idx, *kick, (0, 0));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_i_kicks() {
+        let build: Build = |dir, axis| Box::new(Piece::new(Rc::new(Shape::i()), dir, axis));
+        for dir in DIRS.iter() {
+            for rotate_dir in [RotateDirection::Left, RotateDirection::Right] {
+                let table = expected_i_kicks(*dir, rotate_dir);
+                for (idx, kick) in table.iter().enumerate() {
+                    assert_kick(build, *dir, rotate_dir, idx, *kick, i_centre(*dir));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_o_kicks() {
+        let axis = Block::new(Color::Yellow, 4, 10);
+        let mut o = Piece::new(Rc::new(Shape::o()), TetrominoDirection::North, axis);
+        let blocks = o.blocks();
+        assert!(o.rotate_in(RotateDirection::Right, &empty_field()));
+        assert_eq!(blocks, o.blocks());
+    }
+
+    #[test]
+    fn test_rotate_in_blocked() {
+        let axis = Block::new(Color::Purple, 4, 10);
+        let mut t = Piece::new(Rc::new(Shape::t()), TetrominoDirection::North, axis);
+        let mut field = empty_field();
+        for kick in t.kicks(RotateDirection::Right).iter() {
+            field.fix_blocks(t.dry_rotate(RotateDirection::Right, kick));
+        }
+        let origin = t.clone();
+        assert!(!t.rotate_in(RotateDirection::Right, &field));
+        assert_eq!(origin, t);
+    }
+
+    // Rotates the piece about its SRS centre and applies the expected kick, then checks the
+    // piece lands there once every earlier kick is obstructed.
+    fn assert_kick(
+        build: Build,
+        dir: TetrominoDirection,
+        rotate_dir: RotateDirection,
+        idx: usize,
+        kick: (i32, i32),
+        centre: (i32, i32),
+    ) {
+        let axis = Block::new(Color::Cyan, 4, 10);
+        let mut tetromino = build(dir, axis);
+        let current = cells(&tetromino.blocks());
+        let (cx, cy) = (2 * axis.x() + centre.0, 2 * axis.y() + centre.1);
+        let mut expected: Vec<(i32, i32)> = current
+            .iter()
+            .map(|(x, y)| {
+                let (dx, dy) = (2 * x - cx, 2 * y - cy);
+                let (rx, ry) = match rotate_dir {
+                    RotateDirection::Right => (cx + dy, cy - dx),
+                    RotateDirection::Left => (cx - dy, cy + dx),
+                };
+                (rx / 2 + kick.0, ry / 2 + kick.1)
+            })
+            .collect();
+        expected.sort();
+
+        let kicks = tetromino.kicks(rotate_dir);
+        let label = format!("{:?} {:?} kick {}", dir, rotate_dir, idx);
+        assert_eq!(
+            expected,
+            cells(&tetromino.dry_rotate(rotate_dir, &kicks[idx])),
+            "{}",
+            label
+        );
+
+        // Some earlier kicks only overlap the piece itself and the target, so they can never be
+        // obstructed; the table lookup above is all there is to check for those.
+        let mut field = empty_field();
+        for earlier in kicks.iter().take(idx) {
+            let obstacles: Vec<Block> = tetromino
+                .dry_rotate(rotate_dir, earlier)
+                .into_iter()
+                .filter(|block| {
+                    let cell = (*block.x(), *block.y());
+                    !expected.contains(&cell) && !current.contains(&cell)
+                })
+                .collect();
+            if obstacles.is_empty() {
+                return;
+            }
+            field.fix_blocks(obstacles);
+        }
+
+        assert!(tetromino.rotate_in(rotate_dir, &field), "{}", label);
+        assert_eq!(expected, cells(&tetromino.blocks()), "{}", label);
+    }
+
+    fn cells(blocks: &[Block]) -> Vec<(i32, i32)> {
+        let mut cells: Vec<(i32, i32)> = blocks
+            .iter()
+            .map(|block| (*block.x(), *block.y()))
+            .collect();
+        cells.sort();
+        cells
+    }
+
+    fn empty_field() -> Field {
+        Field::new(FieldConfig::default())
+    }
+
+    // Doubled offset from the axis to the centre of the I tetromino's 4x4 box.
+    fn i_centre(dir: TetrominoDirection) -> (i32, i32) {
+        match dir {
+            TetrominoDirection::North => (1, -1),
+            TetrominoDirection::East => (-1, -1),
+            TetrominoDirection::South => (-1, 1),
+            TetrominoDirection::West => (1, 1),
+        }
+    }
+
+    fn expected_jlstz_kicks(
+        dir: TetrominoDirection,
+        rotate_dir: RotateDirection,
+    ) -> [(i32, i32); 5] {
+        use RotateDirection::{Left, Right};
+        use TetrominoDirection::{East, North, South, West};
+
+        match (dir, rotate_dir) {
+            (North, Right) => [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+            (East, Left) => [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+            (East, Right) => [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+            (South, Left) => [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+            (South, Right) => [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+            (West, Left) => [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+            (West, Right) => [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+            (North, Left) => [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+        }
+    }
+
+    fn expected_i_kicks(dir: TetrominoDirection, rotate_dir: RotateDirection) -> [(i32, i32); 5] {
+        use RotateDirection::{Left, Right};
+        use TetrominoDirection::{East, North, South, West};
+
+        match (dir, rotate_dir) {
+            (North, Right) => [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+            (East, Left) => [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+            (East, Right) => [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+            (South, Left) => [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+            (South, Right) => [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+            (West, Left) => [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+            (West, Right) => [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+            (North, Left) => [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+        }
+    }
+}
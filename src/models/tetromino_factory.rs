@@ -1,95 +1,133 @@
 use std::collections::VecDeque;
 
+use anyhow::Result;
+
 use crate::models::{
-    block::{Block, Color},
-    tetromino::{i::I, j::J, l::L, o::O, s::S, t::T, z::Z, Tetromino, TetrominoDirection},
+    field::FieldConfig,
+    piece_set::PieceSet,
+    randomizer::{bag::Bag, Randomizer},
+    rng::{clock_seed, Rng},
+    tetromino::{Tetromino, TetrominoKind},
 };
 
 pub struct TetrominoFactory {
-    seven_bag: VecDeque<Box<dyn Tetromino>>,
-    rand: usize,
+    piece_set: PieceSet,
+    field_config: FieldConfig,
+    queue: VecDeque<TetrominoKind>,
+    randomizer: Box<dyn Randomizer>,
+    seed: u64,
+    rng: Rng,
+}
+
+impl Default for TetrominoFactory {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TetrominoFactory {
     pub fn new() -> Self {
-        Self {
-            seven_bag: VecDeque::new(),
-            rand: 0,
-        }
+        Self::with_seed(clock_seed())
     }
 
-    pub fn pop(&mut self) -> Box<dyn Tetromino> {
-        if self.seven_bag.len() < 7 {
-            let mut new_seven_bag = self.new_seven_bag();
-            self.seven_bag.append(&mut new_seven_bag);
+    /// Deals the same pieces every time for the same seed.
+    pub fn with_seed(seed: u64) -> Self {
+        let piece_set = PieceSet::standard();
+        Self {
+            randomizer: Box::new(Bag::new(1, &piece_set.kinds())),
+            piece_set,
+            field_config: FieldConfig::default(),
+            queue: VecDeque::new(),
+            seed,
+            rng: Rng::new(seed),
         }
-        self.seven_bag.pop_front().unwrap()
     }
 
-    fn new_seven_bag(&mut self) -> VecDeque<Box<dyn Tetromino>> {
-        self.fisher_yates_shuffle(&mut Self::build_seven_tetrominos())
+    /// Deals from `piece_set`, with a 7-bag style bag of one of each until told otherwise.
+    pub fn with_piece_set(self, piece_set: PieceSet) -> Self {
+        Self {
+            randomizer: Box::new(Bag::new(1, &piece_set.kinds())),
+            piece_set,
+            queue: VecDeque::new(),
+            ..self
+        }
     }
 
-    fn fisher_yates_shuffle(
-        &mut self,
-        to_shuffle: &mut VecDeque<Box<dyn Tetromino>>,
-    ) -> VecDeque<Box<dyn Tetromino>> {
-        let mut ret = VecDeque::new();
-        let len = to_shuffle.len();
-        for i in (1..=len).rev() {
-            let rand = self.linear_congruential_generate();
-            let idx = rand % i;
-            let removed = to_shuffle.remove(idx).unwrap();
-            ret.push_back(removed);
+    /// Spawns pieces where a field of the given size has them spawn.
+    pub fn with_field_config(self, field_config: FieldConfig) -> Self {
+        Self {
+            field_config,
+            ..self
         }
-        ret
     }
 
-    fn linear_congruential_generate(&mut self) -> usize {
-        let next = (13 * self.rand + 5) % 24;
-        self.rand = next;
-        next
+    /// Deals with `randomizer`, which must deal from the kinds of the piece set.
+    pub fn with_randomizer(self, randomizer: Box<dyn Randomizer>) -> Self {
+        Self {
+            queue: VecDeque::new(),
+            randomizer,
+            ..self
+        }
     }
-}
 
-impl TetrominoFactory {
-    fn build_seven_tetrominos() -> VecDeque<Box<dyn Tetromino>> {
-        let mut ret: VecDeque<Box<dyn Tetromino>> = VecDeque::new();
-        ret.push_back(Box::new(Self::build_default_i()));
-        ret.push_back(Box::new(Self::build_default_j()));
-        ret.push_back(Box::new(Self::build_default_l()));
-        ret.push_back(Box::new(Self::build_default_s()));
-        ret.push_back(Box::new(Self::build_default_z()));
-        ret.push_back(Box::new(Self::build_default_t()));
-        ret.push_back(Box::new(Self::build_default_o()));
-        ret
+    pub fn seed(&self) -> &u64 {
+        &self.seed
     }
 
-    fn build_default_i() -> I {
-        I::new(TetrominoDirection::North, Block::new(Color::Cyan, 4, 20))
+    pub fn pop(&mut self) -> Box<dyn Tetromino> {
+        let kind = self.peek(1)[0];
+        self.queue.pop_front();
+        self.build(kind)
+            .expect("the randomizer deals from the piece set")
     }
 
-    fn build_default_j() -> J {
-        J::new(TetrominoDirection::North, Block::new(Color::Blue, 4, 20))
+    /// Returns the kinds of the next `n` pieces without taking them.
+    pub fn peek(&mut self, n: usize) -> Vec<TetrominoKind> {
+        while self.queue.len() < n {
+            let kind = self.randomizer.next(&mut self.rng);
+            self.queue.push_back(kind);
+        }
+        self.queue.iter().take(n).copied().collect()
     }
 
-    fn build_default_l() -> L {
-        L::new(TetrominoDirection::North, Block::new(Color::Orange, 4, 20))
+    pub fn build(&self, kind: TetrominoKind) -> Result<Box<dyn Tetromino>> {
+        self.piece_set.build(kind, &self.field_config)
     }
+}
 
-    fn build_default_s() -> S {
-        S::new(TetrominoDirection::North, Block::new(Color::Green, 4, 20))
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    fn build_default_z() -> Z {
-        Z::new(TetrominoDirection::North, Block::new(Color::Red, 4, 20))
+    #[test]
+    fn test_with_seed() {
+        let mut factory_1 = TetrominoFactory::with_seed(7);
+        let mut factory_2 = TetrominoFactory::with_seed(7);
+        assert_eq!(factory_1.peek(70), factory_2.peek(70));
+        assert_ne!(factory_1.peek(70), TetrominoFactory::with_seed(8).peek(70));
+        assert_eq!(7, *factory_1.seed());
     }
 
-    fn build_default_t() -> T {
-        T::new(TetrominoDirection::North, Block::new(Color::Purple, 4, 20))
+    #[test]
+    fn test_with_piece_set() {
+        let piece_set = PieceSet::pentominoes();
+        let mut factory = TetrominoFactory::with_seed(1).with_piece_set(piece_set.clone());
+        let mut kinds = factory.peek(18);
+        kinds.sort_by_key(|kind| format!("{:?}", kind));
+        let mut expected = piece_set.kinds();
+        expected.sort_by_key(|kind| format!("{:?}", kind));
+        assert_eq!(expected, kinds);
+        assert_eq!(5, factory.pop().blocks().len());
     }
 
-    fn build_default_o() -> O {
-        O::new(TetrominoDirection::North, Block::new(Color::Yellow, 4, 20))
+    #[test]
+    fn test_peek() {
+        let mut factory = TetrominoFactory::new();
+        let kinds = factory.peek(16);
+        assert_eq!(16, kinds.len());
+        assert_eq!(kinds[..5], factory.peek(5)[..]);
+        for kind in kinds.iter() {
+            assert_eq!(*kind, factory.pop().kind());
+        }
     }
 }
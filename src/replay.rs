@@ -0,0 +1,448 @@
+//! Records the inputs of a game so that it can be played back exactly.
+//!
+//! A game is fully determined by its settings, the seed it deals from and the frame each
+//! input arrives on, since every rule runs on whole frames.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context, Error, Result};
+
+use crate::game::{Game, Input, FRAME};
+use crate::input::button::Button;
+use crate::models::{
+    gravity::Gravity, piece_set::PieceSet, randomizer::RandomizerKind, score::Scoring,
+};
+use crate::settings::{Delay, Settings};
+
+const BUTTONS: [(Button, char); 10] = [
+    (Button::Left, 'L'),
+    (Button::Right, 'R'),
+    (Button::SoftDrop, 'D'),
+    (Button::HardDrop, 'H'),
+    (Button::SonicDrop, 'S'),
+    (Button::RotateLeft, 'Z'),
+    (Button::RotateRight, 'X'),
+    (Button::Hold, 'C'),
+    (Button::ToggleGhost, 'G'),
+    (Button::Restart, 'Q'),
+];
+
+const PIECE_SETS: [&str; 3] = ["standard", "pentominoes", "easy"];
+
+const RANDOMIZERS: [(RandomizerKind, &str); 7] = [
+    (RandomizerKind::SevenBag, "seven_bag"),
+    (RandomizerKind::FourteenBag, "fourteen_bag"),
+    (RandomizerKind::Pure, "pure"),
+    (RandomizerKind::Nes, "nes"),
+    (RandomizerKind::Tgm1, "tgm1"),
+    (RandomizerKind::Tgm2, "tgm2"),
+    (RandomizerKind::Tgm3, "tgm3"),
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    // The settings the game started with, its seed included.
+    settings: Settings,
+    inputs: Vec<(u64, Input)>,
+    frames: u64,
+}
+
+impl Replay {
+    /// Starts recording a game that has just been created. Fails if its pieces can't be
+    /// written out and read back as they are.
+    pub fn new(game: &Game) -> Result<Self> {
+        let piece_set = game.settings().piece_set();
+        if built_in_name(piece_set).is_none()
+            && piece_set.to_string().parse::<PieceSet>().ok().as_ref() != Some(piece_set)
+        {
+            bail!("piece set can't be written to a replay");
+        }
+        Ok(Self {
+            settings: game.settings().clone().with_seed(*game.seed()),
+            inputs: Vec::new(),
+            frames: *game.frame(),
+        })
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn inputs(&self) -> &Vec<(u64, Input)> {
+        &self.inputs
+    }
+
+    pub fn frames(&self) -> &u64 {
+        &self.frames
+    }
+
+    /// Records an input applied on `frame`, which must not be before the last one.
+    pub fn record(&mut self, frame: u64, input: Input) -> Result<()> {
+        let last = self.inputs.last().map_or(0, |(last, _)| *last);
+        if frame < last {
+            bail!("input on frame {} recorded after frame {}", frame, last);
+        }
+        self.inputs.push((frame, input));
+        self.frames = self.frames.max(frame);
+        Ok(())
+    }
+
+    /// Marks the game as having run until `frame`.
+    pub fn advance(&mut self, frame: u64) {
+        self.frames = self.frames.max(frame);
+    }
+
+    /// Plays the inputs back on a new game and returns it as it stands on the last frame.
+    pub fn play(&self) -> Game {
+        let mut game = Game::new(self.settings.clone());
+        for (frame, input) in self.inputs.iter() {
+            game.tick(frame.saturating_sub(*game.frame()) as f64 * FRAME);
+            game.apply(*input);
+        }
+        game.tick(self.frames.saturating_sub(*game.frame()) as f64 * FRAME);
+        game
+    }
+}
+
+// One setting per line, then the inputs on a single line, each as the frames since the one
+// before and its button, upper case when pressed and lower case when released. A set of
+// pieces other than the built-in ones follows its own line to the end.
+impl fmt::Display for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let settings = &self.settings;
+        writeln!(f, "seed {}", settings.seed().unwrap_or_default())?;
+        writeln!(f, "ghost {}", settings.ghost())?;
+        writeln!(f, "lock_delay {}", settings.lock_delay())?;
+        writeln!(f, "max_lock_resets {}", settings.max_lock_resets())?;
+        writeln!(f, "das {}", format_delay(settings.das()))?;
+        writeln!(f, "arr {}", format_delay(settings.arr()))?;
+        writeln!(f, "sdf {}", settings.sdf())?;
+        writeln!(f, "partial_lock_out {}", settings.partial_lock_out())?;
+        writeln!(f, "previews {}", settings.previews())?;
+        let randomizer = RANDOMIZERS
+            .iter()
+            .find(|(kind, _)| kind == settings.randomizer())
+            .map_or("", |(_, name)| name);
+        writeln!(f, "randomizer {}", randomizer)?;
+        let field = settings.field();
+        let (width, height) = (field.width(), field.height());
+        writeln!(f, "field {} {} {}", width, height, field.visible_height())?;
+        writeln!(f, "level {}", settings.level())?;
+        let scoring = match settings.scoring() {
+            Scoring::Guideline => "guideline",
+            Scoring::Nes => "nes",
+        };
+        writeln!(f, "scoring {}", scoring)?;
+        match settings.gravity() {
+            Gravity::Guideline => writeln!(f, "gravity guideline")?,
+            Gravity::Table(table) => {
+                let table: Vec<String> = table.iter().map(|rows| rows.to_string()).collect();
+                writeln!(f, "gravity table {}", table.join(" "))?
+            }
+        }
+        writeln!(f, "frames {}", self.frames)?;
+
+        write!(f, "inputs")?;
+        let mut last = 0;
+        for (frame, input) in self.inputs.iter() {
+            let (button, pressed) = match input {
+                Input::Press(button) => (button, true),
+                Input::Release(button) => (button, false),
+            };
+            let letter = BUTTONS
+                .iter()
+                .find(|(b, _)| b == button)
+                .map_or('?', |(_, letter)| *letter);
+            let letter = if pressed {
+                letter
+            } else {
+                letter.to_ascii_lowercase()
+            };
+            write!(f, " {}{}", frame - last, letter)?;
+            last = *frame;
+        }
+        writeln!(f)?;
+
+        let piece_set = settings.piece_set();
+        match built_in_name(piece_set) {
+            Some(name) => writeln!(f, "pieces {}", name),
+            None => write!(f, "pieces custom\n{}", piece_set),
+        }
+    }
+}
+
+impl FromStr for Replay {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut settings = Settings::default();
+        let mut inputs = Vec::new();
+        let mut frames = 0;
+        let mut lines = s.lines();
+        while let Some(line) = lines.next() {
+            let (key, value) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+            let context = || format!("bad {} in replay", key);
+            settings = match key {
+                "" => settings,
+                "seed" => settings.with_seed(value.parse().with_context(context)?),
+                "ghost" => settings.with_ghost(value.parse().with_context(context)?),
+                "lock_delay" => settings.with_lock_delay(value.parse().with_context(context)?),
+                "max_lock_resets" => {
+                    settings.with_max_lock_resets(value.parse().with_context(context)?)
+                }
+                "das" => settings.with_das(parse_delay(value).with_context(context)?),
+                "arr" => settings.with_arr(parse_delay(value).with_context(context)?),
+                "sdf" => settings.with_sdf(value.parse().with_context(context)?),
+                "partial_lock_out" => {
+                    settings.with_partial_lock_out(value.parse().with_context(context)?)
+                }
+                "previews" => settings.with_previews(value.parse().with_context(context)?),
+                "randomizer" => {
+                    let kind = RANDOMIZERS
+                        .iter()
+                        .find(|(_, name)| *name == value)
+                        .map(|(kind, _)| *kind)
+                        .ok_or_else(|| anyhow!(context()))?;
+                    settings.with_randomizer(kind)
+                }
+                "field" => settings.with_field(value.parse().with_context(context)?)?,
+                "level" => settings.with_level(value.parse().with_context(context)?),
+                "scoring" => match value {
+                    "guideline" => settings.with_scoring(Scoring::Guideline),
+                    "nes" => settings.with_scoring(Scoring::Nes),
+                    _ => bail!(context()),
+                },
+                "gravity" => match value.split_once(' ').unwrap_or((value, "")) {
+                    ("guideline", _) => settings.with_gravity(Gravity::Guideline),
+                    ("table", table) => {
+                        let table = table
+                            .split_whitespace()
+                            .map(|rows| rows.parse())
+                            .collect::<Result<_, _>>()
+                            .with_context(context)?;
+                        settings.with_gravity(Gravity::Table(table))
+                    }
+                    _ => bail!(context()),
+                },
+                "frames" => {
+                    frames = value.parse().with_context(context)?;
+                    settings
+                }
+                "inputs" => {
+                    let mut frame = 0;
+                    for token in value.split_whitespace() {
+                        let letter = token.chars().last().unwrap_or_default();
+                        let delta: u64 = token[..token.len() - letter.len_utf8()]
+                            .parse()
+                            .with_context(context)?;
+                        let button = BUTTONS
+                            .iter()
+                            .find(|(_, l)| *l == letter.to_ascii_uppercase())
+                            .map(|(button, _)| *button)
+                            .ok_or_else(|| anyhow!(context()))?;
+                        frame += delta;
+                        let input = if letter.is_ascii_uppercase() {
+                            Input::Press(button)
+                        } else {
+                            Input::Release(button)
+                        };
+                        inputs.push((frame, input));
+                    }
+                    settings
+                }
+                "pieces" if value == "custom" => {
+                    let rest: Vec<&str> = lines.by_ref().collect();
+                    settings.with_piece_set(rest.join("\n").parse()?)
+                }
+                "pieces" => {
+                    let (name, rule) = value.split_once(' ').unwrap_or((value, ""));
+                    let set = built_in_piece_set(name).ok_or_else(|| anyhow!(context()))?;
+                    match rule {
+                        "" => settings.with_piece_set(set),
+                        "all_spin" => settings.with_piece_set(set.with_all_spin()),
+                        _ => bail!(context()),
+                    }
+                }
+                _ => bail!("unknown setting {} in replay", key),
+            };
+        }
+        if inputs.iter().any(|(frame, _)| *frame > frames) {
+            bail!("replay inputs run past its frames");
+        }
+        Ok(Self {
+            settings,
+            inputs,
+            frames,
+        })
+    }
+}
+
+fn built_in_piece_set(name: &str) -> Option<PieceSet> {
+    match name {
+        "standard" => Some(PieceSet::standard()),
+        "pentominoes" => Some(PieceSet::pentominoes()),
+        "easy" => Some(PieceSet::easy()),
+        _ => None,
+    }
+}
+
+// Names a built-in piece set, with or without the all-spin rule.
+fn built_in_name(piece_set: &PieceSet) -> Option<String> {
+    PIECE_SETS.iter().find_map(|name| {
+        let set = built_in_piece_set(name)?;
+        if *piece_set == set {
+            Some(name.to_string())
+        } else if *piece_set == set.with_all_spin() {
+            Some(format!("{} all_spin", name))
+        } else {
+            None
+        }
+    })
+}
+
+fn format_delay(delay: &Delay) -> String {
+    match delay {
+        Delay::Millis(millis) => format!("{}ms", millis),
+        Delay::Frames(frames) => format!("{}f", frames),
+    }
+}
+
+fn parse_delay(delay: &str) -> Result<Delay> {
+    if let Some(millis) = delay.strip_suffix("ms") {
+        Ok(Delay::Millis(millis.parse()?))
+    } else if let Some(frames) = delay.strip_suffix('f') {
+        Ok(Delay::Frames(frames.parse()?))
+    } else {
+        bail!("unknown delay {}", delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        field::{Field, FieldConfig},
+        rng::Rng,
+        tetromino::shape::Shape,
+    };
+
+    #[test]
+    fn test_play() {
+        let settings = Settings::default()
+            .with_seed(1)
+            .with_randomizer(RandomizerKind::Tgm3)
+            .with_previews(3)
+            .with_piece_set(PieceSet::easy().with_all_spin())
+            .with_gravity(Gravity::Table(vec![0.05, 0.1]));
+        let mut game = Game::new(settings);
+        let mut replay = Replay::new(&game).unwrap();
+        // Each piece goes to the lowest column after a few random inputs, with uneven ticks
+        // in between, so that the game clears some lines before it tops out.
+        let mut rng = Rng::new(7);
+        for _ in 0..300 {
+            let extras = [Button::Hold, Button::RotateLeft, Button::RotateRight];
+            for _ in 0..rng.below(3) {
+                let button = extras[rng.below(extras.len())];
+                tap(&mut game, &mut replay, &mut rng, button);
+            }
+            let target = (0..10)
+                .min_by_key(|x| height(game.field(), *x))
+                .unwrap_or(0);
+            let blocks = game.tetromino().blocks();
+            let left = blocks.iter().map(|block| *block.x()).min().unwrap_or(0);
+            let button = if target < left {
+                Button::Left
+            } else {
+                Button::Right
+            };
+            for _ in 0..(target - left).abs() {
+                tap(&mut game, &mut replay, &mut rng, button);
+            }
+            if rng.below(4) == 0 {
+                tap(&mut game, &mut replay, &mut rng, Button::SoftDrop);
+            }
+            tap(&mut game, &mut replay, &mut rng, Button::HardDrop);
+        }
+        assert!(*game.pieces() > 50);
+        assert!(*game.score().lines() > 0);
+
+        let text = replay.to_string();
+        let parsed: Replay = text.parse().unwrap();
+        assert_eq!(replay, parsed);
+        let played = parsed.play();
+        assert_eq!(game.field(), played.field());
+        assert_eq!(game.score(), played.score());
+        assert_eq!(game.pieces(), played.pieces());
+        assert_eq!(game.frame(), played.frame());
+        assert_eq!(game.state(), played.state());
+    }
+
+    #[test]
+    fn test_from_str() {
+        let settings = Settings::default()
+            .with_seed(42)
+            .with_das(Delay::Frames(10))
+            .with_arr(Delay::Millis(0.0))
+            .with_scoring(Scoring::Nes)
+            .with_field(FieldConfig::new(6, 14, 10))
+            .unwrap()
+            .with_piece_set("A red\n#@#\n\nB gray\n@\n#".parse().unwrap());
+        let game = Game::new(settings);
+        let mut replay = Replay::new(&game).unwrap();
+        replay.record(3, Input::Press(Button::Left)).unwrap();
+        replay.record(3, Input::Press(Button::RotateRight)).unwrap();
+        replay.record(20, Input::Release(Button::Left)).unwrap();
+        replay.advance(25);
+        let text = replay.to_string();
+        assert!(text.contains("inputs 3L 0X 17l\n"));
+        assert_eq!(replay, text.parse().unwrap());
+
+        assert!("frames 2\ninputs 3L".parse::<Replay>().is_err());
+        assert!("inputs 3K".parse::<Replay>().is_err());
+        assert!("speed 3".parse::<Replay>().is_err());
+        assert!("field 0 24 20".parse::<Replay>().is_err());
+        assert!("field 65 24 20".parse::<Replay>().is_err());
+        assert!("field 10 22 20".parse::<Replay>().is_err());
+    }
+
+    #[test]
+    fn test_record() {
+        let mut replay = Replay::new(&Game::new(Settings::default())).unwrap();
+        assert!(replay.record(5, Input::Press(Button::Left)).is_ok());
+        assert!(replay.record(4, Input::Release(Button::Left)).is_err());
+        assert!(replay.record(5, Input::Release(Button::Left)).is_ok());
+        assert_eq!(2, replay.inputs().len());
+    }
+
+    #[test]
+    fn test_new() {
+        let new = |piece_set: PieceSet| {
+            let settings = Settings::default().with_piece_set(piece_set);
+            Replay::new(&Game::new(settings))
+        };
+        assert!(new(PieceSet::standard()).is_ok());
+        assert!(new(PieceSet::pentominoes().with_all_spin()).is_ok());
+        assert!(new("A red\n#@#".parse().unwrap()).is_ok());
+        // Built in code, these keep the kicks of the standard pieces, which text can't hold.
+        let names = vec![String::from("I"), String::from("O")];
+        assert!(new(PieceSet::new(names, vec![Shape::i(), Shape::o()])).is_err());
+    }
+
+    fn height(field: &Field, x: i32) -> usize {
+        let rows = field.rows();
+        rows.iter()
+            .rposition(|row| row & (1 << x) != 0)
+            .map_or(0, |y| y + 1)
+    }
+
+    // Presses and releases `button`, recording both, with a short random wait after each.
+    fn tap(game: &mut Game, replay: &mut Replay, rng: &mut Rng, button: Button) {
+        for input in [Input::Press(button), Input::Release(button)] {
+            replay.record(*game.frame(), input).unwrap();
+            game.apply(input);
+            game.tick(rng.below(40) as f64 + 0.5);
+            replay.advance(*game.frame());
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use anyhow::Result;
+
+use crate::models::{
+    field::FieldConfig, gravity::Gravity, piece_set::PieceSet, randomizer::RandomizerKind,
+    score::Scoring,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    ghost: bool,
+    lock_delay: f64,
+    max_lock_resets: u32,
+    das: Delay,
+    arr: Delay,
+    sdf: f64,
+    partial_lock_out: bool,
+    previews: usize,
+    seed: Option<u64>,
+    randomizer: RandomizerKind,
+    piece_set: PieceSet,
+    field: FieldConfig,
+    level: u32,
+    scoring: Scoring,
+    gravity: Gravity,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Delay {
+    Millis(f64),
+    Frames(u32),
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            ghost: true,
+            lock_delay: 500.0,
+            max_lock_resets: 15,
+            das: Delay::Millis(167.0),
+            arr: Delay::Frames(2),
+            sdf: 20.0,
+            partial_lock_out: false,
+            previews: 5,
+            seed: None,
+            randomizer: RandomizerKind::SevenBag,
+            piece_set: PieceSet::standard(),
+            field: FieldConfig::default(),
+            level: 1,
+            scoring: Scoring::Guideline,
+            gravity: Gravity::Guideline,
+        }
+    }
+}
+
+impl Settings {
+    pub fn ghost(&self) -> &bool {
+        &self.ghost
+    }
+
+    pub fn lock_delay(&self) -> &f64 {
+        &self.lock_delay
+    }
+
+    pub fn max_lock_resets(&self) -> &u32 {
+        &self.max_lock_resets
+    }
+
+    pub fn das(&self) -> &Delay {
+        &self.das
+    }
+
+    pub fn arr(&self) -> &Delay {
+        &self.arr
+    }
+
+    pub fn sdf(&self) -> &f64 {
+        &self.sdf
+    }
+
+    pub fn partial_lock_out(&self) -> &bool {
+        &self.partial_lock_out
+    }
+
+    pub fn previews(&self) -> &usize {
+        &self.previews
+    }
+
+    pub fn seed(&self) -> &Option<u64> {
+        &self.seed
+    }
+
+    pub fn randomizer(&self) -> &RandomizerKind {
+        &self.randomizer
+    }
+
+    pub fn piece_set(&self) -> &PieceSet {
+        &self.piece_set
+    }
+
+    pub fn field(&self) -> &FieldConfig {
+        &self.field
+    }
+
+    pub fn level(&self) -> &u32 {
+        &self.level
+    }
+
+    pub fn scoring(&self) -> &Scoring {
+        &self.scoring
+    }
+
+    pub fn gravity(&self) -> &Gravity {
+        &self.gravity
+    }
+
+    pub fn with_ghost(self, ghost: bool) -> Self {
+        Self { ghost, ..self }
+    }
+
+    pub fn with_lock_delay(self, lock_delay: f64) -> Self {
+        Self { lock_delay, ..self }
+    }
+
+    pub fn with_max_lock_resets(self, max_lock_resets: u32) -> Self {
+        Self {
+            max_lock_resets,
+            ..self
+        }
+    }
+
+    pub fn with_das(self, das: Delay) -> Self {
+        Self { das, ..self }
+    }
+
+    pub fn with_arr(self, arr: Delay) -> Self {
+        Self { arr, ..self }
+    }
+
+    pub fn with_sdf(self, sdf: f64) -> Self {
+        Self { sdf, ..self }
+    }
+
+    pub fn with_partial_lock_out(self, partial_lock_out: bool) -> Self {
+        Self {
+            partial_lock_out,
+            ..self
+        }
+    }
+
+    /// Shows between 1 and 6 upcoming pieces.
+    pub fn with_previews(self, previews: usize) -> Self {
+        Self {
+            previews: previews.clamp(1, 6),
+            ..self
+        }
+    }
+
+    /// Deals from a fixed seed instead of one taken from the clock.
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            seed: Some(seed),
+            ..self
+        }
+    }
+
+    pub fn with_randomizer(self, randomizer: RandomizerKind) -> Self {
+        Self { randomizer, ..self }
+    }
+
+    pub fn with_piece_set(self, piece_set: PieceSet) -> Self {
+        Self { piece_set, ..self }
+    }
+
+    pub fn with_field(self, field: FieldConfig) -> Result<Self> {
+        let (width, height) = (*field.width(), *field.height());
+        let field = FieldConfig::try_new(width, height, *field.visible_height())?;
+        Ok(Self { field, ..self })
+    }
+
+    pub fn with_level(self, level: u32) -> Self {
+        Self { level, ..self }
+    }
+
+    pub fn with_scoring(self, scoring: Scoring) -> Self {
+        Self { scoring, ..self }
+    }
+
+    pub fn with_gravity(self, gravity: Gravity) -> Self {
+        Self { gravity, ..self }
+    }
+
+    pub fn toggle_ghost(&mut self) {
+        self.ghost = !self.ghost;
+    }
+}
+
+impl Delay {
+    pub fn millis(&self) -> f64 {
+        match self {
+            Delay::Millis(millis) => *millis,
+            Delay::Frames(frames) => *frames as f64 * 1000.0 / 60.0,
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use anyhow::Result;
+
+use retrospector::render::{draw_image, Position, Renderer, SpriteStore};
+
+use crate::models::{
+    block::{Block, Color},
+    field::FieldConfig,
+    score::Score,
+};
+
+const BLOCK_SIZE: f64 = 32.0;
+
+/// The canvas fits the visible field plus the hold and preview columns to its right.
+pub fn canvas_size(config: &FieldConfig) -> (f64, f64) {
+    let width = (*config.width() + 10) as f64 * BLOCK_SIZE;
+    let height = *config.visible_height() as f64 * BLOCK_SIZE;
+    (width, height)
+}
+
+pub fn render_block(
+    block: &Block,
+    config: &FieldConfig,
+    renderer: &Renderer,
+    tetromino_sprites: &SpriteStore,
+) -> Result<()> {
+    draw(
+        block,
+        config,
+        renderer,
+        tetromino_sprites,
+        sprite_idx(block),
+    )
+}
+
+pub fn render_ghost_block(
+    block: &Block,
+    config: &FieldConfig,
+    renderer: &Renderer,
+    tetromino_sprites: &SpriteStore,
+) -> Result<()> {
+    // The outlined variants follow the solid ones on the sprite sheet.
+    draw(
+        block,
+        config,
+        renderer,
+        tetromino_sprites,
+        sprite_idx(block) + 8,
+    )
+}
+
+pub fn render_hud(score: &Score) {
+    let mut text = format!(
+        "SCORE {}  LEVEL {}  LINES {}",
+        score.points(),
+        score.level(),
+        score.lines()
+    );
+    if let Some(combo) = score.combo().filter(|combo| *combo > 0) {
+        text.push_str(&format!("  COMBO {}", combo));
+    }
+    if *score.back_to_back() {
+        text.push_str("  B2B");
+    }
+    let hud = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.get_element_by_id("hud"));
+    if let Some(hud) = hud {
+        if hud.text_content().as_deref() != Some(text.as_str()) {
+            hud.set_text_content(Some(&text));
+        }
+    }
+}
+
+fn sprite_idx(block: &Block) -> usize {
+    match block.color() {
+        Color::Cyan => 1,
+        Color::Blue => 2,
+        Color::Orange => 3,
+        Color::Green => 4,
+        Color::Red => 5,
+        Color::Purple => 6,
+        Color::Yellow => 7,
+        Color::Gray => 0,
+    }
+}
+
+fn draw(
+    block: &Block,
+    config: &FieldConfig,
+    renderer: &Renderer,
+    tetromino_sprites: &SpriteStore,
+    col: usize,
+) -> Result<()> {
+    if let Ok(sprite) = tetromino_sprites.sprite(col) {
+        let top = *config.visible_height() as f64 - 1.0;
+        let position = Position::new(
+            *block.x() as f64 * BLOCK_SIZE,
+            (top - *block.y() as f64) * BLOCK_SIZE,
+        );
+        draw_image(renderer, &sprite, position)?;
+    }
+
+    Ok(())
+}